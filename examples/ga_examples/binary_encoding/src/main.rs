@@ -1,4 +1,4 @@
-use mincost::{Evolution, EvolutionConfig, Individual};
+use mincost::{BitFlipMutation, Evolution, EvolutionConfig, Individual, NanPolicy, Objective, Rng};
 use std::iter::repeat_with;
 fn main() {
     // give hyper parameter in ga
@@ -7,23 +7,26 @@ fn main() {
         elite_size: 20,
         mutation_rate: 0.4,
         generations: 10,
+        objective: Objective::Maximize,
+        nan_policy: NanPolicy::Error,
     };
     // define randness strategy
-    let randness = || -> Individual<bool> {
+    let randness = |rng: &Rng| -> Individual<bool> {
         Individual {
-            genes: repeat_with(|| rand::random::<bool>()).take(10).collect(),
+            genes: repeat_with(|| rng.bool()).take(10).collect(),
         }
     };
     // define fitness function
     let fitness = |solution: &Individual<bool>| -> i32 {
         let weight: Vec<i32> = (-5..5).collect();
         solution.genes.iter().zip(weight).fold(0, |acc, (g, w)| {
-            let score = if *g == true { w } else { -w };
+            let score = if *g { w } else { -w };
             acc + score
         })
     };
-    // construct ga
-    let mut evolution = Evolution::init(evolution_config, fitness, randness);
+    // construct ga, flipping bits on mutation
+    let mut evolution = Evolution::init(evolution_config, fitness, randness)
+        .with_mutation(BitFlipMutation { rate: 0.1 });
     let best_ind = evolution.evolute();
     println!("Best Individual {:?}", best_ind);
 }
@@ -1,5 +1,4 @@
-use mincost::{Evolution, EvolutionConfig, Individual};
-use rand::{thread_rng, Rng};
+use mincost::{Evolution, EvolutionConfig, Individual, NanPolicy, Objective, Rng, SigmaTruncation};
 use std::iter::repeat_with;
 
 fn main() {
@@ -9,12 +8,13 @@ fn main() {
         elite_size: 20,
         mutation_rate: 0.1,
         generations: 20,
+        objective: Objective::Maximize,
+        nan_policy: NanPolicy::Error,
     };
     // define randness strategy
-    let randness = || -> Individual<i32> {
-        let mut rng = thread_rng();
+    let randness = |rng: &Rng| -> Individual<i32> {
         Individual {
-            genes: repeat_with(|| rng.gen_range(100..200)).take(10).collect(),
+            genes: repeat_with(|| rng.i32(100..200)).take(10).collect(),
         }
     };
 
@@ -27,8 +27,9 @@ fn main() {
         });
         sum as f32
     };
-    // construct ga
-    let mut evolution = Evolution::init(evolution_config, fitness, randness);
+    // construct ga, scaling the negative fitness before roulette selection
+    let mut evolution = Evolution::init(evolution_config, fitness, randness)
+        .with_scaling(SigmaTruncation::default());
     let best_ind = evolution.evolute();
     println!("Best DeciIndividual {:?}", best_ind);
 }
@@ -1,6 +1,4 @@
-use mincost::{Evolution, EvolutionConfig, Individual};
-use rand::prelude::SliceRandom;
-use rand::thread_rng;
+use mincost::{Evolution, EvolutionConfig, Individual, NanPolicy, Objective, OrderCrossover, Rng};
 fn main() {
     // say if you want to travel 8 cities in China: 0.Beijing 1.Shanghai 2.Hangzhou 3.Wuhan 4.Chengdu 5.Nanjing
     // 6.Chongqing 7.Guangzhou
@@ -22,12 +20,13 @@ fn main() {
         elite_size: 4,
         mutation_rate: 0.1,
         generations: 10,
+        objective: Objective::Minimize,
+        nan_policy: NanPolicy::Error,
     };
     // define randness strategy
-    let randness = || -> Individual<usize> {
-        let mut rng = thread_rng();
+    let randness = |rng: &Rng| -> Individual<usize> {
         let mut index = vec![0, 1, 2, 3, 4, 5, 6, 7];
-        index.shuffle(&mut rng);
+        rng.shuffle(&mut index);
         Individual { genes: index }
     };
     // define fitness function
@@ -39,8 +38,11 @@ fn main() {
             acc + dis_matrix[i][j]
         })
     };
-    // construct ga
-    let mut evolution = Evolution::init(evolution_config, fitness, randness);
+    // construct ga from a fixed seed so every run finds the same route,
+    // breeding children without duplicated cities
+    let rng = Rng::with_seed(7);
+    let mut evolution = Evolution::init_with_rng(evolution_config, fitness, randness, rng)
+        .with_crossover(OrderCrossover);
     let best_ind = evolution.evolute();
     println!("Total Path Length :{:?} km", fitness(&best_ind));
     println!(
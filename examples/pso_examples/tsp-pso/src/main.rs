@@ -1,3 +1,48 @@
+use mincost::{Particle, PsOpt, PsoConfig, Rng};
 fn main() {
-    println!("Hello, world!");
+    // say if you want to travel 8 cities in China: 0.Beijing 1.Shanghai 2.Hangzhou 3.Wuhan 4.Chengdu 5.Nanjing
+    // 6.Chongqing 7.Guangzhou
+
+    // distance matrix of 8 cities(in km), source from https://www.distancecalculator.net
+    let dis_matrix = [
+        [0, 1213, 1120, 1160, 1516, 896, 1456, 1885],
+        [1213, 0, 161, 687, 1655, 268, 1434, 1206],
+        [1120, 161, 0, 566, 1542, 239, 1309, 1045],
+        [1160, 687, 566, 0, 978, 459, 754, 836],
+        [1516, 1655, 1542, 978, 0, 1404, 269, 1238],
+        [896, 268, 239, 239, 459, 0, 1199, 1131],
+        [1456, 1434, 1309, 754, 269, 1199, 0, 979],
+        [1885, 1206, 1045, 836, 1238, 1131, 979, 0],
+    ];
+    // each city holds a continuous key, the route visits cities by ascending key
+    let route = |keys: &[f32]| -> Vec<usize> {
+        let mut index: Vec<usize> = (0..keys.len()).collect();
+        index.sort_by(|a, b| keys[*a].partial_cmp(&keys[*b]).unwrap());
+        index
+    };
+    // give hyper parameter in pso
+    let pso_config = PsoConfig::builder(30, 50)
+        .omega(0.6)
+        .build()
+        .expect("valid hyper parameter");
+    // define randness strategy, keys are within 0 to 1
+    let randness = |rng: &Rng| -> Particle<f32> { Particle::random(&[(0.0, 1.0); 8], rng) };
+    // define fitness function
+    let fitness = |keys: &[f32]| -> f32 {
+        let index = route(keys);
+        (0..7).fold(0, |acc, idx| acc + dis_matrix[index[idx]][index[idx + 1]]) as f32
+    };
+    // construct pso
+    let mut pso = PsOpt::init(pso_config, fitness, randness);
+    let best_keys = pso.optimize();
+    println!("Total Path Length :{:?} km", fitness(&best_keys));
+    println!("Fitness Evaluations: {}", pso.evaluations());
+    println!(
+        "Best Travel Route: {:?}",
+        route(&best_keys)
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join(" -> ")
+    );
 }
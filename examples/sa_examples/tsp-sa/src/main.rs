@@ -1,6 +1,4 @@
-use mincost::{Annealer, AnnealerConfig, Solution};
-use rand::prelude::SliceRandom;
-use rand::thread_rng;
+use mincost::{Annealer, AnnealerConfig, NanPolicy, Objective, Rng, Solution};
 fn main() {
     // say if you want to travel 8 cities in China: 0.Beijing 1.Shanghai 2.Hangzhou 3.Wuhan 4.Chengdu 5.Nanjing
     // 6.Chongqing 7.Guangzhou
@@ -21,13 +19,15 @@ fn main() {
         alpha: 0.8,
         temperature_zero: 80.0,
         temperature_end: 5.0,
-        iteration: 10,
+        iteration: 20,
+        moves_per_temperature: 10,
+        objective: Objective::Minimize,
+        nan_policy: NanPolicy::Error,
     };
     // define randness strategy
-    let randness = || -> Solution<usize> {
-        let mut rng = thread_rng();
+    let randness = |rng: &Rng| -> Solution<usize> {
         let mut index = vec![0, 1, 2, 3, 4, 5, 6, 7];
-        index.shuffle(&mut rng);
+        rng.shuffle(&mut index);
         Solution { bits: index }
     };
     // define fitness function
@@ -0,0 +1,39 @@
+//! Errors of fallible runs
+//!
+//! The `try_*` variants of the run methods report a [`MincostError`] instead of
+//! panicking, so a bad fitness or config does not abort the embedding program.
+//! ```ignore
+//! match evolution.try_evolute() {
+//!     Ok(best_ind) => println!("{:?}", best_ind),
+//!     Err(MincostError::InvalidFitness(cost)) => eprintln!("simulation failed: {}", cost),
+//!     Err(e) => eprintln!("{}", e),
+//! }
+//! ```
+use std::fmt;
+
+/// error aborting a run
+#[derive(Debug, PartialEq, Clone)]
+pub enum MincostError {
+    /// an operator is applied to a population in the wrong state, ie. breeding
+    /// before selection
+    InvalidState(&'static str),
+    /// the fitness of a solution is NaN or infinite
+    InvalidFitness(f64),
+    /// a solution has no gene
+    EmptyGenome,
+    /// a hyper parameter is out of range, with the reason
+    InvalidConfig(String),
+}
+
+impl fmt::Display for MincostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MincostError::InvalidState(reason) => write!(f, "invalid state: {}", reason),
+            MincostError::InvalidFitness(cost) => write!(f, "invalid fitness: {}", cost),
+            MincostError::EmptyGenome => write!(f, "solution without any gene"),
+            MincostError::InvalidConfig(reason) => write!(f, "invalid config: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for MincostError {}
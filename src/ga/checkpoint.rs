@@ -0,0 +1,109 @@
+use super::{Evolution, EvolutionConfig, Individual, Population, PopulationStatus};
+use super::{RouletteSelection, SegmentCrossover, SwapMutation};
+use crate::parallel::MaybeSync;
+use crate::termination::{RunCheckpoint, RunState};
+use crate::{MaxIterations, Rng};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// saved state of an evolution between two generations
+///
+/// The checkpoint holds the config, the ranked population, the best individual,
+/// the generation counter, the history and the state of the `Rng`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvolutionCheckpoint<T> {
+    config: EvolutionConfig,
+    individuals: Vec<Individual<T>>,
+    #[serde(with = "crate::non_finite::vec_option")]
+    fitness: Vec<Option<f64>>,
+    best: Individual<T>,
+    #[serde(with = "crate::non_finite")]
+    best_cost: f64,
+    evaluations: usize,
+    run: RunCheckpoint,
+    rng_seed: u64,
+}
+
+impl<T, F, O> Evolution<T, F>
+where
+    F: Fn(&Individual<T>) -> O + MaybeSync,
+    O: PartialOrd + Into<f64>,
+    T: Copy + Debug + std::cmp::PartialEq + MaybeSync,
+{
+    /// save the state of the evolution, to be serialized
+    pub fn checkpoint(&self) -> EvolutionCheckpoint<T> {
+        EvolutionCheckpoint {
+            config: self.config,
+            individuals: self.population.individuals.clone(),
+            fitness: self.population.fitness.clone(),
+            best: self.best.clone(),
+            best_cost: self.best_cost,
+            evaluations: self.evaluations,
+            run: self.run_state.checkpoint(),
+            rng_seed: self.rng.get_seed(),
+        }
+    }
+    /// continue the evolution where the checkpoint was saved
+    ///
+    /// Operators, scaling, termination and observers start from the defaults of
+    /// [`Evolution::init`]. A [`WindowScaling`](super::WindowScaling) set again starts
+    /// with an empty window, so the resumed run only matches the saved one once the
+    /// window is filled again.
+    pub fn resume(checkpoint: EvolutionCheckpoint<T>, fitness: F) -> Self {
+        let config = checkpoint.config;
+        Evolution {
+            config,
+            population: Population {
+                individuals: checkpoint.individuals,
+                fitness: checkpoint.fitness,
+                status: PopulationStatus::Ranked,
+            },
+            fitness,
+            crossover: Box::new(SegmentCrossover),
+            selection: Box::new(RouletteSelection),
+            scaling: None,
+            mutation: Box::new(SwapMutation),
+            evaluations: checkpoint.evaluations,
+            best: checkpoint.best,
+            best_cost: checkpoint.best_cost,
+            termination: Box::new(MaxIterations(config.generations)),
+            observers: vec![],
+            run_state: RunState::resume(checkpoint.run),
+            rng: Rng::with_seed(checkpoint.rng_seed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::evolution;
+    use super::*;
+    use crate::testing::{assert_resumed_run_matches, failing_tour, random_tour, tour};
+    use crate::NanPolicy;
+
+    #[test]
+    fn resumed_run_matches_uninterrupted_run() {
+        assert_resumed_run_matches(
+            || evolution(3),
+            15,
+            Evolution::checkpoint,
+            |checkpoint| Evolution::resume(checkpoint, tour as fn(&_) -> f64),
+        );
+    }
+
+    #[test]
+    fn resumed_run_keeps_worst_fitness() {
+        let config = EvolutionConfig::builder(30, 40)
+            .nan_policy(NanPolicy::Worst)
+            .build()
+            .unwrap();
+        let fitness = failing_tour as fn(&_) -> f64;
+        let saved = assert_resumed_run_matches(
+            || Evolution::init_with_rng(config, fitness, random_tour, Rng::with_seed(3)),
+            15,
+            Evolution::checkpoint,
+            |checkpoint| Evolution::resume(checkpoint, fitness),
+        );
+        assert!(saved.contains("\"inf\""));
+    }
+}
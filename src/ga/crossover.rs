@@ -0,0 +1,343 @@
+//! Crossover operators
+//!
+//! A [`Crossover`] breeds one child from two selected parents. The segment
+//! crossovers work on any encoding, while PMX, OX1, CX and ERX expect both
+//! parents to be permutations of the same genes and always breed a permutation.
+use super::Individual;
+use crate::{MincostError, Rng};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// strategy to breed a child from two parents
+pub trait Crossover<T> {
+    /// produce one child from two parents, drawing randomness from `rng`
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        rng: &Rng,
+    ) -> Individual<T>;
+    /// error if the operator can not breed parents of `len` genes, checked before a
+    /// fallible run; any length fits by default
+    fn check(&self, _len: usize) -> Result<(), MincostError> {
+        Ok(())
+    }
+}
+
+/// Segment crossover, the default breed method.
+///
+/// A random segment of the first parent is appended to the genes of the
+/// second parent that lie outside the segment. Works for any encoding.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SegmentCrossover;
+
+/// Unique segment crossover for permutation encoding.
+///
+/// A random segment of the first parent is appended to the genes of the
+/// second parent that are not in the segment, so the child never holds
+/// duplicated genes.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct UniqueSegmentCrossover;
+
+impl<T> Crossover<T> for SegmentCrossover
+where
+    T: Copy + Debug + PartialEq,
+{
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        rng: &Rng,
+    ) -> Individual<T> {
+        parent1.breed1(parent2, rng)
+    }
+}
+
+impl<T> Crossover<T> for UniqueSegmentCrossover
+where
+    T: Copy + Debug + PartialEq,
+{
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        rng: &Rng,
+    ) -> Individual<T> {
+        parent1.breed2(parent2, rng)
+    }
+}
+
+/// Partially mapped crossover (PMX) for permutation encoding.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PartiallyMappedCrossover;
+
+/// Order crossover (OX1) for permutation encoding.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct OrderCrossover;
+
+/// Cycle crossover (CX) for permutation encoding.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct CycleCrossover;
+
+/// Edge recombination crossover (ERX) for permutation encoding.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct EdgeRecombinationCrossover;
+
+// random gene segment in `start..end`
+fn random_segment(len: usize, rng: &Rng) -> (usize, usize) {
+    let idx1: usize = rng.usize(..len);
+    let idx2: usize = rng.usize(..len);
+    (std::cmp::min(idx1, idx2), std::cmp::max(idx1, idx2))
+}
+
+/// Crossover operators for permutation encoding.
+///
+/// Both parents must be permutations of the same genes, then the child is
+/// guaranteed to be a valid permutation as well.
+impl<T> Individual<T>
+where
+    T: Copy + Eq + Hash,
+{
+    /// Partially mapped crossover. The child inherits a random segment of
+    /// `self`, other genes come from `another` and are mapped through the
+    /// segment when they collide with it.
+    pub fn partially_mapped_crossover(&self, another: &Self, rng: &Rng) -> Self {
+        if self.genes.is_empty() {
+            return self.clone();
+        }
+        let (start, end) = random_segment(self.genes.len(), rng);
+        let position: HashMap<T, usize> = self
+            .genes
+            .iter()
+            .enumerate()
+            .map(|(i, g)| (*g, i))
+            .collect();
+        let genes = (0..self.genes.len())
+            .map(|i| {
+                if (start..end).contains(&i) {
+                    return self.genes[i];
+                }
+                let mut gene = another.genes[i];
+                // follow the mapping until gene is out of segment
+                while let Some(j) = position.get(&gene).filter(|j| (start..end).contains(*j)) {
+                    gene = another.genes[*j];
+                }
+                gene
+            })
+            .collect();
+        Individual { genes }
+    }
+    /// Order crossover (OX1). The child inherits a random segment of `self`,
+    /// the rest is filled in the order of `another` starting after the segment.
+    pub fn order_crossover(&self, another: &Self, rng: &Rng) -> Self {
+        let len = self.genes.len();
+        if len == 0 {
+            return self.clone();
+        }
+        let (start, end) = random_segment(len, rng);
+        let inherited: HashSet<T> = self.genes[start..end].iter().cloned().collect();
+        let mut genes = self.genes.clone();
+        let mut filler = (0..len)
+            .map(|i| another.genes[(end + i) % len])
+            .filter(|g| !inherited.contains(g));
+        for i in 0..len - (end - start) {
+            genes[(end + i) % len] = filler.next().unwrap();
+        }
+        Individual { genes }
+    }
+    /// Cycle crossover. Genes are split into position cycles between parents,
+    /// the child takes the cycles from `self` and `another` alternately.
+    pub fn cycle_crossover(&self, another: &Self) -> Self {
+        let len = self.genes.len();
+        let position: HashMap<T, usize> = self
+            .genes
+            .iter()
+            .enumerate()
+            .map(|(i, g)| (*g, i))
+            .collect();
+        let mut genes = self.genes.clone();
+        let mut visited = vec![false; len];
+        let mut from_self = true;
+        for start in 0..len {
+            if visited[start] {
+                continue;
+            }
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                genes[i] = if from_self {
+                    self.genes[i]
+                } else {
+                    another.genes[i]
+                };
+                i = position[&another.genes[i]];
+            }
+            from_self = !from_self;
+        }
+        Individual { genes }
+    }
+    /// Edge recombination crossover. The child is walked along edges shared
+    /// by both parents, preferring the neighbor with fewest remaining edges.
+    pub fn edge_recombination_crossover(&self, another: &Self, rng: &Rng) -> Self {
+        let len = self.genes.len();
+        if len == 0 {
+            return self.clone();
+        }
+        // union of neighbors of each gene in both parents
+        let mut edges: HashMap<T, Vec<T>> = HashMap::with_capacity(len);
+        for parent in [&self.genes, &another.genes].iter() {
+            for i in 0..len {
+                let neighbors = edges.entry(parent[i]).or_default();
+                for n in [parent[(i + len - 1) % len], parent[(i + 1) % len]].iter() {
+                    if *n != parent[i] && !neighbors.contains(n) {
+                        neighbors.push(*n);
+                    }
+                }
+            }
+        }
+        let mut genes = Vec::with_capacity(len);
+        let mut visited: HashSet<T> = HashSet::with_capacity(len);
+        let mut current = self.genes[0];
+        loop {
+            genes.push(current);
+            visited.insert(current);
+            if genes.len() == len {
+                break;
+            }
+            let neighbors = edges.remove(&current).unwrap_or_default();
+            for n in neighbors.iter() {
+                if let Some(e) = edges.get_mut(n) {
+                    e.retain(|g| *g != current);
+                }
+            }
+            let fewest = neighbors.iter().map(|n| edges[n].len()).min();
+            current = match fewest {
+                Some(fewest) => {
+                    let candidates: Vec<T> = neighbors
+                        .into_iter()
+                        .filter(|n| edges[n].len() == fewest)
+                        .collect();
+                    candidates[rng.usize(..candidates.len())]
+                }
+                // dead end, restart from a random unvisited gene
+                None => {
+                    let candidates: Vec<T> = self
+                        .genes
+                        .iter()
+                        .filter(|g| !visited.contains(g))
+                        .cloned()
+                        .collect();
+                    candidates[rng.usize(..candidates.len())]
+                }
+            };
+        }
+        Individual { genes }
+    }
+}
+
+impl<T> Crossover<T> for PartiallyMappedCrossover
+where
+    T: Copy + Eq + Hash,
+{
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        rng: &Rng,
+    ) -> Individual<T> {
+        parent1.partially_mapped_crossover(parent2, rng)
+    }
+}
+
+impl<T> Crossover<T> for OrderCrossover
+where
+    T: Copy + Eq + Hash,
+{
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        rng: &Rng,
+    ) -> Individual<T> {
+        parent1.order_crossover(parent2, rng)
+    }
+}
+
+impl<T> Crossover<T> for CycleCrossover
+where
+    T: Copy + Eq + Hash,
+{
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        _rng: &Rng,
+    ) -> Individual<T> {
+        parent1.cycle_crossover(parent2)
+    }
+}
+
+impl<T> Crossover<T> for EdgeRecombinationCrossover
+where
+    T: Copy + Eq + Hash,
+{
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        rng: &Rng,
+    ) -> Individual<T> {
+        parent1.edge_recombination_crossover(parent2, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permutation(len: usize, rng: &Rng) -> Individual<usize> {
+        let mut genes: Vec<usize> = (0..len).collect();
+        rng.shuffle(&mut genes);
+        Individual { genes }
+    }
+
+    // breed random parents of each length many times, every child must hold each gene once
+    fn assert_permutation<C: Crossover<usize>>(crossover: C) {
+        let rng = Rng::with_seed(42);
+        for len in [1, 2, 3, 10] {
+            for _ in 0..200 {
+                let parent1 = permutation(len, &rng);
+                let parent2 = permutation(len, &rng);
+                let mut genes = crossover.crossover(&parent1, &parent2, &rng).genes;
+                genes.sort_unstable();
+                assert_eq!(genes, (0..len).collect::<Vec<usize>>(), "length {}", len);
+            }
+        }
+    }
+
+    #[test]
+    fn partially_mapped_child_is_permutation() {
+        assert_permutation(PartiallyMappedCrossover);
+    }
+
+    #[test]
+    fn order_child_is_permutation() {
+        assert_permutation(OrderCrossover);
+    }
+
+    #[test]
+    fn cycle_child_is_permutation() {
+        assert_permutation(CycleCrossover);
+    }
+
+    #[test]
+    fn edge_recombination_child_is_permutation() {
+        assert_permutation(EdgeRecombinationCrossover);
+    }
+
+    #[test]
+    fn unique_segment_child_is_permutation() {
+        assert_permutation(UniqueSegmentCrossover);
+    }
+}
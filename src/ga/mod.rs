@@ -3,40 +3,94 @@
 //! Four steps to construct generic algorithm
 //!
 //! 1. Give hyper parameter in GA
-//! ``` rust
+//! ```ignore
 //! let config = EvolutionConfig {...}
 //! ```
 
-//! 2. Define individual encoding and its randomization
-//! ``` rust
+//! 2. Define individual encoding and its randomization, drawing from the given `Rng`
+//! ```ignore
 //! use std::iter::repeat_with;
-//! let randness = || -> Individual<bool> {
+//! let randness = |rng: &Rng| -> Individual<bool> {
 //!     Individual {
-//!         genes: repeat_with(|| rand::random::<bool>()).take(10).collect(),
+//!         genes: repeat_with(|| rng.bool()).take(10).collect(),
 //!     }
 //! };
 //! ```
 //! 3. Define fitness function by closure
-//! ``` rust
+//! ```ignore
 //! let fitness = |solution: &Individual<bool>| -> f32 {
 //!     ...
 //! };
 //! ```
 //! 4. Construct genetic algorithm
-//! ``` rust
+//! ```ignore
 //! let mut evolution = Evolution::init(config, fitness, randness);
 //! ```
 
+//! Optionally, choose other crossover, selection and mutation operators for the evolution
+//! ```ignore
+//! let mut evolution = Evolution::init(config, fitness, randness)
+//!     .with_crossover(OrderCrossover)
+//!     .with_selection(TournamentSelection { size: 3 })
+//!     .with_mutation(
+//!         CompositeMutation::new()
+//!             .with(0.5, InversionMutation)
+//!             .with(0.5, SwapMutation),
+//!     );
+//! ```
+
+//! Scale scores before selection, ie. for proportional selection of negative fitness
+//! ```ignore
+//! let mut evolution = Evolution::init(config, fitness, randness).with_scaling(SigmaTruncation { c: 2.0 });
+//! ```
+
+//! To reproduce a run, construct the evolution from a seeded `Rng`
+//! ```ignore
+//! let mut evolution = Evolution::init_with_rng(config, fitness, randness, Rng::with_seed(42));
+//! ```
+
 //! Finally, run the process to get the optimized solution
-//! ``` rust
+//! ```ignore
 //! let best_ind = evolution.evolute();
 //! ```
 
+//! With the `serde` feature, save the evolution and continue it later
+//! ```ignore
+//! let checkpoint = serde_json::to_string(&evolution.checkpoint())?;
+//! let mut evolution = Evolution::resume(serde_json::from_str(&checkpoint)?, fitness);
+//! ```
+
 //! Learn more from the [examples](examples/ga_examples)
+use crate::error::MincostError;
+use crate::observer;
+use crate::parallel::{self, MaybeSync};
+use crate::termination::RunState;
+use crate::{
+    DynFitness, IterationStats, MaxIterations, NanPolicy, Objective, Observer, Optimizer, Problem,
+    Progress, Rng, RunHistory, Snapshot, StopReason, Termination,
+};
 use std::fmt::Debug;
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+mod checkpoint;
+mod crossover;
+mod mutation;
+mod real;
+mod scaling;
+mod selection;
+
+#[cfg(feature = "serde")]
+pub use checkpoint::*;
+pub use crossover::*;
+pub use mutation::*;
+pub use real::*;
+pub use scaling::*;
+pub use selection::*;
 
 /// generic individual to support various encoding style
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Individual<T> {
     /// genes in the individual
     pub genes: Vec<T>,
@@ -47,10 +101,23 @@ pub struct Evolution<T, F> {
     config: EvolutionConfig,
     population: Population<T>,
     fitness: F,
+    crossover: Box<dyn Crossover<T>>,
+    selection: Box<dyn Selection>,
+    // scaling of scores before selection, raw scores if `None`
+    scaling: Option<Box<dyn Scaling>>,
+    mutation: Box<dyn Mutation<T>>,
+    evaluations: usize,
+    best: Individual<T>,
+    best_cost: f64,
+    termination: Box<dyn Termination>,
+    observers: Vec<Box<dyn Observer<T>>>,
+    run_state: RunState,
+    rng: Rng,
 }
 
 /// hyper parameter in genetic algorithm
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EvolutionConfig {
     /// population size
     pub pop_size: usize,
@@ -60,6 +127,79 @@ pub struct EvolutionConfig {
     pub mutation_rate: f32,
     /// evolution generation number
     pub generations: usize,
+    /// whether to minimize or maximize the fitness
+    pub objective: Objective,
+    /// what becomes of an individual with NaN or infinite fitness
+    pub nan_policy: NanPolicy,
+}
+
+/// builder of an [`EvolutionConfig`], validated as it is built
+#[derive(Debug, Copy, Clone)]
+pub struct EvolutionConfigBuilder {
+    config: EvolutionConfig,
+}
+
+impl EvolutionConfig {
+    /// builder of `pop_size` individuals evolving `generations` times, with defaults of
+    /// `elite_size = 0`, `mutation_rate = 0.1`, minimized fitness and [`NanPolicy::Error`]
+    pub fn builder(pop_size: usize, generations: usize) -> EvolutionConfigBuilder {
+        EvolutionConfigBuilder {
+            config: EvolutionConfig {
+                pop_size,
+                elite_size: 0,
+                mutation_rate: 0.1,
+                generations,
+                objective: Objective::Minimize,
+                nan_policy: NanPolicy::Error,
+            },
+        }
+    }
+    /// error describing the first hyper parameter out of range
+    pub fn validate(&self) -> Result<(), MincostError> {
+        if self.pop_size == 0 {
+            Err(MincostError::InvalidConfig("pop_size is 0".to_string()))
+        } else if self.elite_size > self.pop_size {
+            Err(MincostError::InvalidConfig(format!(
+                "elite_size {} exceeds pop_size {}",
+                self.elite_size, self.pop_size
+            )))
+        } else if !(0.0..=1.0).contains(&self.mutation_rate) {
+            Err(MincostError::InvalidConfig(format!(
+                "mutation_rate {} is not within 0 to 1",
+                self.mutation_rate
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl EvolutionConfigBuilder {
+    /// individuals kept intact from one generation to the next
+    pub fn elite_size(mut self, elite_size: usize) -> Self {
+        self.config.elite_size = elite_size;
+        self
+    }
+    /// probability to mutate a child, in 0 to 1
+    pub fn mutation_rate(mut self, mutation_rate: f32) -> Self {
+        self.config.mutation_rate = mutation_rate;
+        self
+    }
+    /// whether to minimize or maximize the fitness
+    pub fn objective(mut self, objective: Objective) -> Self {
+        self.config.objective = objective;
+        self
+    }
+    /// what becomes of an individual with NaN or infinite fitness
+    pub fn nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.config.nan_policy = nan_policy;
+        self
+    }
+    /// the config, or an error describing the first hyper parameter out of range
+    pub fn build(self) -> Result<EvolutionConfig, MincostError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
 }
 
 impl<T> Individual<T>
@@ -67,9 +207,9 @@ where
     T: Copy + Debug + std::cmp::PartialEq,
 {
     // breed1, default breed method.
-    fn breed1(&self, another: &Self) -> Self {
-        let idx1: usize = fastrand::usize(..self.genes.len());
-        let idx2: usize = fastrand::usize(..self.genes.len());
+    fn breed1(&self, another: &Self, rng: &Rng) -> Self {
+        let idx1: usize = rng.usize(..self.genes.len());
+        let idx2: usize = rng.usize(..self.genes.len());
         let start_gene_idx = std::cmp::min(idx1, idx2);
         let end_gene_idx = std::cmp::max(idx1, idx2);
         let child_p1 = &self.genes[start_gene_idx..end_gene_idx];
@@ -82,9 +222,9 @@ where
         Individual { genes: child_p2 }
     }
     // breed2, special breed method. It ensures the child's gene bit is non-duplicated with each other
-    fn breed2(&self, another: &Self) -> Self {
-        let idx1: usize = fastrand::usize(..self.genes.len());
-        let idx2: usize = fastrand::usize(..self.genes.len());
+    fn breed2(&self, another: &Self, rng: &Rng) -> Self {
+        let idx1: usize = rng.usize(..self.genes.len());
+        let idx2: usize = rng.usize(..self.genes.len());
         let start_gene_idx = std::cmp::min(idx1, idx2);
         let end_gene_idx = std::cmp::max(idx1, idx2);
         let child_p1 = &self.genes[start_gene_idx..end_gene_idx];
@@ -100,13 +240,11 @@ where
         Individual { genes: child_p2 }
     }
     // self-mutated
-    fn mutate(&mut self) {
-        let idx1: usize = fastrand::usize(..self.genes.len());
-        let idx2: usize = fastrand::usize(..self.genes.len());
+    fn mutate(&mut self, rng: &Rng) {
+        let idx1: usize = rng.usize(..self.genes.len());
+        let idx2: usize = rng.usize(..self.genes.len());
         // swap gene within chromo
-        let tmp = self.genes[idx1];
-        self.genes[idx1] = self.genes[idx2];
-        self.genes[idx2] = tmp;
+        self.genes.swap(idx1, idx2);
     }
 }
 
@@ -122,43 +260,302 @@ enum PopulationStatus {
 
 impl<T, F, O> Evolution<T, F>
 where
-    F: Fn(&Individual<T>) -> O,
+    F: Fn(&Individual<T>) -> O + MaybeSync,
     O: PartialOrd + Into<f64>,
-    T: Copy + Debug + std::cmp::PartialEq,
+    T: Copy + Debug + std::cmp::PartialEq + MaybeSync,
 {
-    /// initial envolution, including population and evolution hyper parameter
-    pub fn init<R: Fn() -> Individual<T>>(
+    /// initial envolution, including population and evolution hyper parameter, panics on an
+    /// invalid config
+    pub fn init<R: Fn(&Rng) -> Individual<T>>(
         config: EvolutionConfig,
         fitness: F,
         randness: R,
     ) -> Self {
-        let population = Population::initial_random_pop(config.pop_size, randness);
-        Evolution {
+        Self::init_with_rng(config, fitness, randness, Rng::new())
+    }
+    /// initial envolution drawing all randomness from `rng`, a seeded `Rng` makes the run reproducible,
+    /// panics on an invalid config
+    pub fn init_with_rng<R: Fn(&Rng) -> Individual<T>>(
+        config: EvolutionConfig,
+        fitness: F,
+        randness: R,
+        rng: Rng,
+    ) -> Self {
+        Self::try_init_with_rng(config, fitness, randness, rng).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// initial envolution, or an error for an invalid config or initial fitness
+    pub fn try_init<R: Fn(&Rng) -> Individual<T>>(
+        config: EvolutionConfig,
+        fitness: F,
+        randness: R,
+    ) -> Result<Self, MincostError> {
+        Self::try_init_with_rng(config, fitness, randness, Rng::new())
+    }
+    /// initial envolution drawing all randomness from `rng`, or an error for an invalid
+    /// config or initial fitness
+    pub fn try_init_with_rng<R: Fn(&Rng) -> Individual<T>>(
+        config: EvolutionConfig,
+        fitness: F,
+        randness: R,
+        rng: Rng,
+    ) -> Result<Self, MincostError> {
+        config.validate()?;
+        let mut population = Population::initial_random_pop(config.pop_size, &randness, &rng);
+        let evaluations = population.evaluate(&fitness, &config, |ind: &mut Individual<T>| {
+            *ind = randness(&rng)
+        })?;
+        population.rank(&config)?;
+        let (best, best_cost) = population.best_individual()?;
+        let mut evolution = Evolution {
             config,
             population,
             fitness,
+            crossover: Box::new(SegmentCrossover),
+            selection: Box::new(RouletteSelection),
+            scaling: None,
+            mutation: Box::new(SwapMutation),
+            evaluations,
+            best,
+            best_cost,
+            termination: Box::new(MaxIterations(config.generations)),
+            observers: vec![],
+            run_state: RunState::new(),
+            rng,
+        };
+        evolution.log_population();
+        Ok(evolution)
+    }
+    /// replace the crossover operator, [`SegmentCrossover`] by default
+    pub fn with_crossover<C: Crossover<T> + 'static>(mut self, crossover: C) -> Self {
+        self.crossover = Box::new(crossover);
+        self
+    }
+    /// replace the selection operator, [`RouletteSelection`] by default
+    pub fn with_selection<S: Selection + 'static>(mut self, selection: S) -> Self {
+        self.selection = Box::new(selection);
+        self
+    }
+    /// scale scores before selection, raw scores by default
+    pub fn with_scaling<S: Scaling + 'static>(mut self, scaling: S) -> Self {
+        self.scaling = Some(Box::new(scaling));
+        self
+    }
+    /// replace the mutation operator, [`SwapMutation`] by default
+    pub fn with_mutation<M: Mutation<T> + 'static>(mut self, mutation: M) -> Self {
+        self.mutation = Box::new(mutation);
+        self
+    }
+    /// replace the stopping condition, [`MaxIterations`] of `generations` by default
+    pub fn with_termination<C: Termination + 'static>(mut self, termination: C) -> Self {
+        self.termination = Box::new(termination);
+        self
+    }
+    /// register an observer invoked after every generation
+    pub fn with_observer<B: Observer<T> + 'static>(mut self, observer: B) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+    /// fitness evaluations spent so far
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+    /// progress of the run so far, the diversity is the mean proportion of genes
+    /// differing from the best individual of the population
+    pub fn progress(&self) -> Progress {
+        self.run_state.progress(
+            self.evaluations,
+            self.best_cost,
+            Some(self.population.diversity()),
+            self.config.objective,
+        )
+    }
+    /// criterion which stopped the last run, `None` if it has not stopped yet
+    pub fn stop_reason(&self) -> Option<&StopReason> {
+        self.run_state.stop_reason()
+    }
+    /// statistics of every generation so far, over the fitness of the population
+    pub fn history(&self) -> &RunHistory {
+        self.run_state.history()
+    }
+    // record statistics of the ranked population
+    fn log_population(&mut self) {
+        let costs: Vec<f64> = self.population.fitness.iter().map(|x| x.unwrap()).collect();
+        let stats = IterationStats {
+            diversity: Some(self.population.diversity()),
+            ..IterationStats::new(
+                self.run_state.iteration(),
+                self.evaluations,
+                &costs,
+                self.best_cost,
+                self.config.objective,
+            )
+        };
+        self.run_state.log(stats);
+    }
+    // replace the ranked population by the next ranked generation
+    fn next_generation(&mut self) -> Result<(), MincostError> {
+        let mut selected = self.population.selection(
+            &self.config,
+            self.selection.as_ref(),
+            self.scaling.as_mut(),
+            &self.rng,
+        )?;
+        let mut breeded = selected.breed(&self.config, self.crossover.as_ref(), &self.rng)?;
+        breeded.mutate(&self.config, self.mutation.as_ref(), &self.rng)?;
+        let mutation = self.mutation.as_ref();
+        let rng = &self.rng;
+        self.evaluations +=
+            breeded.evaluate(&self.fitness, &self.config, |ind| mutation.mutate(ind, rng))?;
+        breeded.rank(&self.config)?;
+        let (best, best_cost) = breeded.best_individual()?;
+        let improved = self.config.objective.is_better(&best_cost, &self.best_cost);
+        if improved {
+            self.best = best;
+            self.best_cost = best_cost;
         }
+        self.population = breeded;
+        self.run_state.record(improved);
+        self.log_population();
+        self.notify();
+        Ok(())
     }
-    // generate next iteration
-    fn next_generation(&mut self) -> Population<T> {
-        self.population.rank(&self.fitness);
-        let mut selected = self.population.selection(&self.config, &self.fitness);
-        let mut breeded = selected.breed(&self.config);
-        breeded.mutate(&self.config);
-        breeded
+    // show the generation to observers
+    fn notify(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let progress = self.progress();
+        let snapshot = Snapshot {
+            progress,
+            stats: *self.run_state.history().last().unwrap(),
+            best: &self.best.genes,
+            population: self
+                .population
+                .individuals
+                .iter()
+                .map(|ind| &ind.genes[..])
+                .collect(),
+        };
+        if observer::notify(&mut self.observers, &snapshot) {
+            self.run_state.request_stop();
+        }
     }
-    // the top evolution
+    // the top evolution until the termination fires,
+    // returns the best individual over all generations
     pub fn evolute(&mut self) -> Individual<T> {
-        for _ in 0..self.config.generations {
-            let next_gen: Population<T> = self.next_generation();
-            self.population = next_gen;
+        self.try_evolute().unwrap_or_else(|e| panic!("{}", e))
+    }
+    // error if an operator does not fit the genomes of the population
+    // error for an invalid config, genome or operator, before breeding
+    fn check(&self) -> Result<(), MincostError> {
+        self.config.validate()?;
+        self.population.check_genomes()?;
+        self.check_operators()
+    }
+    fn check_operators(&self) -> Result<(), MincostError> {
+        for ind in self.population.individuals.iter() {
+            self.crossover.check(ind.genes.len())?;
+            self.mutation.check(ind.genes.len())?;
         }
-        self.population.best_individual()
+        Ok(())
+    }
+    /// evolute until the termination fires, reporting an invalid state, fitness,
+    /// genome or config as error instead of panicking
+    pub fn try_evolute(&mut self) -> Result<Individual<T>, MincostError> {
+        self.check()?;
+        loop {
+            let progress = self.progress();
+            if self
+                .run_state
+                .should_stop(self.termination.as_ref(), &progress)
+            {
+                break;
+            }
+            self.next_generation()?;
+        }
+        Ok(self.best.clone())
+    }
+}
+
+impl<T> Evolution<T, DynFitness<Individual<T>>>
+where
+    T: Copy + Debug + std::cmp::PartialEq + MaybeSync + 'static,
+{
+    /// initial envolution solving `problem`, mutating by its mutation hook
+    pub fn from_problem<P>(config: EvolutionConfig, problem: P) -> Self
+    where
+        P: Problem<T> + 'static,
+        Arc<P>: MaybeSync,
+    {
+        Self::from_problem_with_rng(config, problem, Rng::new())
+    }
+    /// initial envolution solving `problem`, drawing all randomness from `rng`
+    pub fn from_problem_with_rng<P>(config: EvolutionConfig, problem: P, rng: Rng) -> Self
+    where
+        P: Problem<T> + 'static,
+        Arc<P>: MaybeSync,
+    {
+        let problem = Arc::new(problem);
+        let config = EvolutionConfig {
+            objective: problem.objective(),
+            ..config
+        };
+        let randness = {
+            let problem = problem.clone();
+            move |rng: &Rng| Individual {
+                genes: problem.random(rng),
+            }
+        };
+        let fitness: DynFitness<Individual<T>> = {
+            let problem = problem.clone();
+            Box::new(move |ind: &Individual<T>| problem.cost(&ind.genes))
+        };
+        Self::init_with_rng(config, fitness, randness, rng).with_mutation(ProblemMutation(problem))
+    }
+}
+
+// mutation by the hook of a problem
+struct ProblemMutation<P>(Arc<P>);
+
+impl<T: Clone, P: Problem<T>> Mutation<T> for ProblemMutation<P> {
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        self.0.mutate(&mut individual.genes, rng);
+    }
+}
+
+impl<T, F, O> Optimizer<T> for Evolution<T, F>
+where
+    F: Fn(&Individual<T>) -> O + MaybeSync,
+    O: PartialOrd + Into<f64>,
+    T: Copy + Debug + std::cmp::PartialEq + MaybeSync,
+{
+    /// breed one generation
+    fn try_step(&mut self) -> Result<bool, MincostError> {
+        self.check()?;
+        self.next_generation()?;
+        Ok(!self.run_state.stop_requested())
+    }
+    fn run(&mut self) -> Vec<T> {
+        self.evolute().genes
+    }
+    fn best(&self) -> &[T] {
+        &self.best.genes
+    }
+    fn best_cost(&self) -> f64 {
+        self.best_cost
+    }
+    fn stop_reason(&self) -> Option<&StopReason> {
+        self.run_state.stop_reason()
+    }
+    fn history(&self) -> &RunHistory {
+        self.run_state.history()
     }
 }
 
 struct Population<T> {
     individuals: Vec<Individual<T>>,
+    // cached fitness of each individual, `None` until it is evaluated
+    fitness: Vec<Option<f64>>,
     status: PopulationStatus,
 }
 
@@ -169,107 +566,302 @@ where
     T: Copy + Debug + std::cmp::PartialEq,
 {
     // initial random population
-    fn initial_random_pop<R: Fn() -> Individual<T>>(pop_size: usize, randness: R) -> Self {
+    fn initial_random_pop<R: Fn(&Rng) -> Individual<T>>(
+        pop_size: usize,
+        randness: &R,
+        rng: &Rng,
+    ) -> Self {
         Population {
-            individuals: repeat_with(|| randness()).take(pop_size).collect(),
+            individuals: repeat_with(|| randness(rng)).take(pop_size).collect(),
+            fitness: vec![None; pop_size],
             status: PopulationStatus::Initialized,
         }
     }
-    // inplace rank between individuals by fitness
-    fn rank<O: PartialOrd>(&mut self, fitness: &dyn Fn(&Individual<T>) -> O) {
-        self.individuals
-            .sort_by(|a, b| fitness(a).partial_cmp(&fitness(b)).unwrap());
+    // evaluate individuals without cached fitness under the NaN policy of the config,
+    // `resample` redraws a rejected individual; returns the number of evaluations
+    fn evaluate<F, O, S>(
+        &mut self,
+        fitness: &F,
+        config: &EvolutionConfig,
+        mut resample: S,
+    ) -> Result<usize, MincostError>
+    where
+        F: Fn(&Individual<T>) -> O + MaybeSync,
+        O: Into<f64>,
+        T: MaybeSync,
+        S: FnMut(&mut Individual<T>),
+    {
+        let mut evaluations = 0;
+        for attempt in 0.. {
+            let pending: Vec<usize> = (0..self.fitness.len())
+                .filter(|i| self.fitness[*i].is_none())
+                .collect();
+            if pending.is_empty() {
+                break;
+            }
+            let individuals: Vec<&Individual<T>> =
+                pending.iter().map(|i| &self.individuals[*i]).collect();
+            let evaluated =
+                parallel::evaluate(&individuals, &|ind: &Individual<T>| fitness(ind).into());
+            evaluations += evaluated.len();
+            for (i, cost) in pending.into_iter().zip(evaluated) {
+                match config.nan_policy.apply(cost, config.objective, attempt)? {
+                    Some(cost) => self.fitness[i] = Some(cost),
+                    None => resample(&mut self.individuals[i]),
+                }
+            }
+        }
+        Ok(evaluations)
+    }
+    // inplace rank between evaluated individuals by fitness, the best individual first
+    fn rank(&mut self, config: &EvolutionConfig) -> Result<(), MincostError> {
+        if self.fitness.iter().any(Option::is_none) {
+            return Err(MincostError::InvalidState(
+                "ranking requires an evaluated population",
+            ));
+        }
+        let mut ranked: Vec<(Individual<T>, Option<f64>)> = self
+            .individuals
+            .drain(..)
+            .zip(self.fitness.drain(..))
+            .collect();
+        // evaluated fitness is never NaN, so it is totally ordered
+        ranked.sort_by(|a, b| {
+            config
+                .objective
+                .ordering(&a.1, &b.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let (individuals, fitness) = ranked.into_iter().unzip();
+        self.individuals = individuals;
+        self.fitness = fitness;
         self.status = PopulationStatus::Ranked;
+        Ok(())
     }
     // individual selection within popultion
-    fn selection<O: PartialOrd>(
+    fn selection(
         &mut self,
         config: &EvolutionConfig,
-        fitness: &dyn Fn(&Individual<T>) -> O,
-    ) -> Self
-    where
-        O: PartialOrd + Into<f64>,
-    {
+        selection: &dyn Selection,
+        scaling: Option<&mut Box<dyn Scaling>>,
+        rng: &Rng,
+    ) -> Result<Self, MincostError> {
+        if self.status != PopulationStatus::Ranked {
+            return Err(MincostError::InvalidState(
+                "selection requires a ranked population",
+            ));
+        }
         let mut selected = Vec::with_capacity(config.pop_size);
-        let mut tmp: f64 = 0.0;
-        let fitness: Vec<f64> = self.individuals.iter().map(|x| fitness(x).into()).collect();
-        let fitness_sum: f64 = fitness.iter().fold(0.0, |acc, x| acc + x);
-        let cum_perc: Vec<i32> = fitness
+        let mut selected_fitness = Vec::with_capacity(config.pop_size);
+        // a ranked population is fully evaluated
+        let score: Vec<f64> = self
+            .fitness
             .iter()
-            .map(|x| {
-                tmp += x;
-                (100.0 * tmp / fitness_sum) as i32
-            })
+            .map(|x| config.objective.score(x.unwrap_or(f64::NAN)))
             .collect();
-        if self.status == PopulationStatus::Ranked {
-            // keep elite from last generation
-            for i in 0..config.elite_size {
-                selected.push(self.individuals[i].clone());
-            }
-            // select high score individuals to form the complete generation
-            for _ in 0..config.pop_size - config.elite_size {
-                let pick = (100.0 * fastrand::f32()) as i32;
-                for j in 0..config.pop_size {
-                    if pick <= cum_perc[j] {
-                        selected.push(self.individuals[j].clone());
-                        break;
-                    }
-                }
-            }
-            Population {
-                individuals: selected,
-                status: PopulationStatus::Selected,
-            }
-        } else {
-            unreachable!()
+        let score = match scaling {
+            Some(scaling) => scaling.scale(&score),
+            None => score,
+        };
+        // keep elite from last generation
+        for i in 0..config.elite_size {
+            selected.push(self.individuals[i].clone());
+            selected_fitness.push(self.fitness[i]);
         }
+        // select high score individuals to form the complete generation
+        for j in selection.select(&score, config.pop_size - config.elite_size, rng) {
+            selected.push(self.individuals[j].clone());
+            selected_fitness.push(self.fitness[j]);
+        }
+        Ok(Population {
+            individuals: selected,
+            fitness: selected_fitness,
+            status: PopulationStatus::Selected,
+        })
     }
     // individual breed within population
-    fn breed(&mut self, config: &EvolutionConfig) -> Self {
+    fn breed(
+        &mut self,
+        config: &EvolutionConfig,
+        crossover: &dyn Crossover<T>,
+        rng: &Rng,
+    ) -> Result<Self, MincostError> {
+        if self.status != PopulationStatus::Selected {
+            return Err(MincostError::InvalidState(
+                "breeding requires a selected population",
+            ));
+        }
         let mut child = Vec::with_capacity(config.pop_size);
-        if self.status == PopulationStatus::Selected {
-            // keep elite from selected result
-            for i in 0..config.elite_size {
-                child.push(self.individuals[i].clone());
-            }
+        let mut child_fitness = Vec::with_capacity(config.pop_size);
+        // keep elite from selected result, along with their fitness
+        for i in 0..config.elite_size {
+            child.push(self.individuals[i].clone());
+            child_fitness.push(self.fitness[i]);
+        }
 
-            for i in 0..config.pop_size - config.elite_size {
-                // parents
-                let p1 = &self.individuals[i];
-                let p2 = &self.individuals[config.pop_size - i - 1];
-                if cfg!(feature = "breed2") {
-                    child.push(p1.breed2(&p2));
-                } else if cfg!(feature = "breed1") {
-                    child.push(p1.breed1(&p2));
-                }
-            }
-            Population {
-                individuals: child,
-                status: PopulationStatus::Breeded,
-            }
-        } else {
-            unreachable!()
+        for i in 0..config.pop_size - config.elite_size {
+            // parents
+            let p1 = &self.individuals[i];
+            let p2 = &self.individuals[config.pop_size - i - 1];
+            child.push(crossover.crossover(p1, p2, rng));
+            child_fitness.push(None);
         }
+        Ok(Population {
+            individuals: child,
+            fitness: child_fitness,
+            status: PopulationStatus::Breeded,
+        })
     }
     // mutation within population
-    fn mutate(&mut self, config: &EvolutionConfig) {
-        if self.status == PopulationStatus::Breeded {
-            for ind in self.individuals.iter_mut() {
-                if fastrand::f32() < config.mutation_rate {
-                    ind.mutate();
-                }
+    fn mutate(
+        &mut self,
+        config: &EvolutionConfig,
+        mutation: &dyn Mutation<T>,
+        rng: &Rng,
+    ) -> Result<(), MincostError> {
+        if self.status != PopulationStatus::Breeded {
+            return Err(MincostError::InvalidState(
+                "mutation requires a breeded population",
+            ));
+        }
+        // elites are kept intact
+        let mutable = self.individuals.iter_mut().zip(self.fitness.iter_mut());
+        for (ind, cached) in mutable.skip(config.elite_size) {
+            if rng.f32() < config.mutation_rate {
+                mutation.mutate(ind, rng);
+                *cached = None;
             }
-            self.status = PopulationStatus::Mutated;
-        } else {
-            unreachable!()
         }
+        self.status = PopulationStatus::Mutated;
+        Ok(())
+    }
+    // mean proportion of genes differing from the best individual of ranked population
+    fn diversity(&self) -> f64 {
+        let best = &self.individuals[0].genes;
+        if best.is_empty() {
+            return 0.0;
+        }
+        let differing: usize = self
+            .individuals
+            .iter()
+            .map(|ind| ind.genes.iter().zip(best).filter(|(a, b)| a != b).count())
+            .sum();
+        differing as f64 / (best.len() * self.individuals.len()) as f64
     }
-    // choose the best individual from population
-    fn best_individual(&self) -> Individual<T> {
-        if self.status == PopulationStatus::Mutated {
-            self.individuals[0].clone()
+    // choose the best individual from ranked population, along with its fitness
+    fn best_individual(&self) -> Result<(Individual<T>, f64), MincostError> {
+        match (
+            self.status == PopulationStatus::Ranked,
+            self.fitness.first(),
+        ) {
+            (true, Some(Some(cost))) => Ok((self.individuals[0].clone(), *cost)),
+            _ => Err(MincostError::InvalidState(
+                "the best individual requires a ranked population",
+            )),
+        }
+    }
+    // error if any individual has no gene
+    fn check_genomes(&self) -> Result<(), MincostError> {
+        if self.individuals.iter().any(|ind| ind.genes.is_empty()) {
+            Err(MincostError::EmptyGenome)
         } else {
-            unreachable!()
+            Ok(())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_same_run, random_tour, tour};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    pub(super) fn evolution(seed: u64) -> Evolution<usize, fn(&Individual<usize>) -> f64> {
+        let config = EvolutionConfig::builder(30, 40)
+            .elite_size(5)
+            .mutation_rate(0.2)
+            .build()
+            .unwrap();
+        Evolution::init_with_rng(
+            config,
+            tour as fn(&_) -> f64,
+            random_tour,
+            Rng::with_seed(seed),
+        )
+    }
+
+    #[test]
+    fn same_seed_gives_identical_run() {
+        assert_same_run(|| {
+            evolution(3)
+                .with_crossover(OrderCrossover)
+                .with_selection(TournamentSelection { size: 3 })
+                .with_mutation(InversionMutation)
+        });
+    }
+
+    #[test]
+    fn each_child_costs_one_evaluation_and_elite_none() {
+        let calls = AtomicUsize::new(0);
+        let counted = |individual: &Individual<usize>| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            tour(individual)
+        };
+        let config = EvolutionConfig::builder(30, 40)
+            .elite_size(5)
+            .mutation_rate(1.0)
+            .build()
+            .unwrap();
+        let mut evolution =
+            Evolution::init_with_rng(config, counted, random_tour, Rng::with_seed(3));
+        assert_eq!(evolution.evaluations(), 30);
+        for generations in 1..=5 {
+            evolution.step();
+            assert_eq!(evolution.evaluations(), 30 + 25 * generations);
+        }
+        evolution.evolute();
+        assert_eq!(evolution.evaluations(), 30 + 25 * 40);
+        assert_eq!(calls.load(Ordering::Relaxed), evolution.evaluations());
+    }
+
+    #[test]
+    fn out_of_range_config_is_rejected() {
+        let invalid = |builder: EvolutionConfigBuilder| {
+            matches!(builder.build(), Err(MincostError::InvalidConfig(_)))
+        };
+        assert!(invalid(EvolutionConfig::builder(0, 5)));
+        assert!(invalid(EvolutionConfig::builder(10, 5).elite_size(11)));
+        assert!(invalid(EvolutionConfig::builder(10, 5).mutation_rate(-0.1)));
+        assert!(invalid(EvolutionConfig::builder(10, 5).mutation_rate(1.5)));
+        assert!(invalid(
+            EvolutionConfig::builder(10, 5).mutation_rate(f32::NAN)
+        ));
+        let config = EvolutionConfig::builder(10, 5)
+            .elite_size(10)
+            .mutation_rate(1.0)
+            .build();
+        assert!(config.is_ok());
+    }
+
+    #[test]
+    fn missing_bound_is_reported_as_error() {
+        let config = EvolutionConfig::builder(10, 5).build().unwrap();
+        let sphere = |ind: &Individual<f64>| ind.genes.iter().map(|x| x * x).sum::<f64>();
+        let randness = |rng: &Rng| Individual {
+            genes: vec![rng.f64(), rng.f64(), rng.f64()],
+        };
+        let mut evolution = Evolution::init_with_rng(config, sphere, randness, Rng::with_seed(1))
+            .with_crossover(ArithmeticCrossover {
+                bounds: vec![(0.0, 1.0); 3],
+            })
+            .with_mutation(GaussianMutation {
+                sigma: 0.1,
+                rate: 0.5,
+                bounds: vec![(0.0, 1.0); 2],
+            });
+        let result = evolution.try_step();
+        assert!(matches!(result, Err(MincostError::InvalidConfig(_))));
+        let result = evolution.try_evolute();
+        assert!(matches!(result, Err(MincostError::InvalidConfig(_))));
+    }
+}
@@ -0,0 +1,257 @@
+//! Mutation operators
+//!
+//! A [`Mutation`] changes a child in place after breeding, with the probability
+//! `mutation_rate` of the config. Swap, inversion, scramble, insertion and
+//! displacement only move genes, so a permutation stays valid, while bit-flip,
+//! random reset and creep change their values. Several operators can be applied
+//! together by [`CompositeMutation`].
+use super::Individual;
+use crate::{MincostError, Rng};
+use std::fmt::Debug;
+
+/// strategy to mutate an individual in place
+pub trait Mutation<T> {
+    /// mutate the individual in place, drawing randomness from `rng`
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng);
+    /// error if the operator can not mutate an individual of `len` genes, checked
+    /// before a fallible run; any length fits by default
+    fn check(&self, _len: usize) -> Result<(), MincostError> {
+        Ok(())
+    }
+}
+
+/// Swap mutation, the default mutation method.
+///
+/// Two random genes exchange their positions.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SwapMutation;
+
+/// Bit-flip mutation for binary encoding.
+///
+/// Each bit is flipped with probability `rate`.
+#[derive(Debug, Copy, Clone)]
+pub struct BitFlipMutation {
+    /// flip probability of each bit, in 0 to 1
+    pub rate: f32,
+}
+
+/// Inversion mutation.
+///
+/// The order of genes in a random segment is reversed.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct InversionMutation;
+
+/// Scramble mutation.
+///
+/// Genes in a random segment are shuffled.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ScrambleMutation;
+
+/// Insertion mutation.
+///
+/// A random gene is removed and inserted back at another random position.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct InsertionMutation;
+
+/// Displacement mutation.
+///
+/// A random segment is removed and inserted back at another random position.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct DisplacementMutation;
+
+/// Random reset mutation.
+///
+/// A random gene is replaced by a new value drawn from the gene domain.
+#[derive(Debug, Copy, Clone)]
+pub struct RandomResetMutation<G> {
+    /// draw a random value from the gene domain with the given `Rng`
+    pub domain: G,
+}
+
+/// Creep mutation for integer encoding.
+///
+/// A random gene is moved by a small uniform step in `-step..=step`,
+/// staying within `lower..=upper` and the range of the gene type.
+#[derive(Debug, Copy, Clone)]
+pub struct CreepMutation {
+    /// largest step of the move
+    pub step: u64,
+    /// lower bound of genes, `i128` to hold the bound of any integer gene
+    pub lower: i128,
+    /// upper bound of genes, `i128` to hold the bound of any integer gene
+    pub upper: i128,
+}
+
+/// Composition of mutation operators.
+///
+/// Each operator is applied in order with its own probability.
+pub struct CompositeMutation<T> {
+    operators: Vec<(f32, Box<dyn Mutation<T>>)>,
+}
+
+// random gene segment in `start..=end`
+fn random_span(len: usize, rng: &Rng) -> (usize, usize) {
+    let idx1: usize = rng.usize(..len);
+    let idx2: usize = rng.usize(..len);
+    (std::cmp::min(idx1, idx2), std::cmp::max(idx1, idx2))
+}
+
+impl<T> Mutation<T> for SwapMutation
+where
+    T: Copy + Debug + PartialEq,
+{
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        individual.mutate(rng);
+    }
+}
+
+impl Mutation<bool> for BitFlipMutation {
+    fn mutate(&self, individual: &mut Individual<bool>, rng: &Rng) {
+        for gene in individual.genes.iter_mut() {
+            if rng.f32() < self.rate {
+                *gene = !*gene;
+            }
+        }
+    }
+}
+
+impl<T> Mutation<T> for InversionMutation {
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        let (start, end) = random_span(individual.genes.len(), rng);
+        individual.genes[start..=end].reverse();
+    }
+}
+
+impl<T> Mutation<T> for ScrambleMutation {
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        let (start, end) = random_span(individual.genes.len(), rng);
+        rng.shuffle(&mut individual.genes[start..=end]);
+    }
+}
+
+impl<T> Mutation<T> for InsertionMutation {
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        let from = rng.usize(..individual.genes.len());
+        let to = rng.usize(..individual.genes.len());
+        let gene = individual.genes.remove(from);
+        individual.genes.insert(to, gene);
+    }
+}
+
+impl<T> Mutation<T> for DisplacementMutation {
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        let (start, end) = random_span(individual.genes.len(), rng);
+        let segment: Vec<T> = individual.genes.drain(start..=end).collect();
+        let to = rng.usize(..=individual.genes.len());
+        individual.genes.splice(to..to, segment);
+    }
+}
+
+impl<T, G> Mutation<T> for RandomResetMutation<G>
+where
+    G: Fn(&Rng) -> T,
+{
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        let idx = rng.usize(..individual.genes.len());
+        individual.genes[idx] = (self.domain)(rng);
+    }
+}
+
+macro_rules! creep_mutation {
+    ($($t:ty),*) => {
+        $(
+            impl Mutation<$t> for CreepMutation {
+                fn mutate(&self, individual: &mut Individual<$t>, rng: &Rng) {
+                    let idx = rng.usize(..individual.genes.len());
+                    // wide enough for any gene and step, then clamped into the gene type
+                    let step = rng.i128(-(self.step as i128)..=self.step as i128);
+                    let lower = self.lower.max(<$t>::MIN as i128);
+                    let upper = self.upper.min(<$t>::MAX as i128);
+                    let gene = (individual.genes[idx] as i128 + step).max(lower).min(upper);
+                    individual.genes[idx] = gene as $t;
+                }
+            }
+        )*
+    };
+}
+
+creep_mutation!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<T> CompositeMutation<T> {
+    /// composition without any operator
+    pub fn new() -> Self {
+        CompositeMutation { operators: vec![] }
+    }
+    /// append an operator applied with probability `rate`
+    pub fn with<M: Mutation<T> + 'static>(mut self, rate: f32, mutation: M) -> Self {
+        self.operators.push((rate, Box::new(mutation)));
+        self
+    }
+}
+
+impl<T> Default for CompositeMutation<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Mutation<T> for CompositeMutation<T> {
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        for (rate, mutation) in self.operators.iter() {
+            if rng.f32() < *rate {
+                mutation.mutate(individual, rng);
+            }
+        }
+    }
+    fn check(&self, len: usize) -> Result<(), MincostError> {
+        for (_, mutation) in self.operators.iter() {
+            mutation.check(len)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creep_stays_within_gene_type() {
+        let rng = Rng::with_seed(1);
+        let creep = CreepMutation {
+            step: 3,
+            lower: -1,
+            upper: 1000,
+        };
+        for _ in 0..200 {
+            let mut individual = Individual { genes: vec![0u8] };
+            creep.mutate(&mut individual, &rng);
+            assert!(individual.genes[0] <= 3);
+            let mut individual = Individual { genes: vec![255u8] };
+            creep.mutate(&mut individual, &rng);
+            assert!(individual.genes[0] >= 252);
+        }
+    }
+
+    #[test]
+    fn creep_moves_extreme_genes_by_small_steps() {
+        let rng = Rng::with_seed(1);
+        let creep = CreepMutation {
+            step: 5,
+            lower: i128::MIN,
+            upper: i128::MAX,
+        };
+        for _ in 0..200 {
+            let mut individual = Individual {
+                genes: vec![u64::MAX],
+            };
+            creep.mutate(&mut individual, &rng);
+            assert!(individual.genes[0] >= u64::MAX - 5);
+            let mut individual = Individual {
+                genes: vec![i64::MIN],
+            };
+            creep.mutate(&mut individual, &rng);
+            assert!(individual.genes[0] <= i64::MIN + 5);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Operators for real-valued encoding
+//!
+//! All operators take one `(lower, upper)` bound per gene and always keep
+//! the genes of the produced individual within their bounds. They panic on a
+//! genome whose length differs from the number of bounds, which
+//! [`Evolution::try_evolute`](super::Evolution::try_evolute) reports as
+//! [`MincostError::InvalidConfig`] before the run instead.
+use super::{Crossover, Individual, Mutation};
+use crate::{MincostError, Rng};
+
+/// real number gene, ie. `f32` and `f64`
+pub trait Real: Copy {
+    /// widen gene to `f64`
+    fn to_f64(self) -> f64;
+    /// narrow `f64` to gene
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Real for f32 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Real for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+/// Simulated binary crossover (SBX).
+#[derive(Debug, Clone)]
+pub struct SimulatedBinaryCrossover {
+    /// distribution index, the larger the closer child stays to parents
+    pub eta: f64,
+    /// `(lower, upper)` bound of each gene
+    pub bounds: Vec<(f64, f64)>,
+}
+
+/// Blend crossover (BLX-α).
+#[derive(Debug, Clone)]
+pub struct BlendCrossover {
+    /// extension of the parents' interval on both sides, 0.5 is common
+    pub alpha: f64,
+    /// `(lower, upper)` bound of each gene
+    pub bounds: Vec<(f64, f64)>,
+}
+
+/// Whole arithmetic crossover.
+#[derive(Debug, Clone)]
+pub struct ArithmeticCrossover {
+    /// `(lower, upper)` bound of each gene
+    pub bounds: Vec<(f64, f64)>,
+}
+
+/// Polynomial mutation.
+#[derive(Debug, Clone)]
+pub struct PolynomialMutation {
+    /// distribution index, the larger the smaller the step
+    pub eta: f64,
+    /// mutation probability of each gene, in 0 to 1
+    pub rate: f32,
+    /// `(lower, upper)` bound of each gene
+    pub bounds: Vec<(f64, f64)>,
+}
+
+/// Gaussian mutation.
+#[derive(Debug, Clone)]
+pub struct GaussianMutation {
+    /// standard deviation of the step, as a proportion of gene's range
+    pub sigma: f64,
+    /// mutation probability of each gene, in 0 to 1
+    pub rate: f32,
+    /// `(lower, upper)` bound of each gene
+    pub bounds: Vec<(f64, f64)>,
+}
+
+// standard normal sample by Box-Muller transform
+fn standard_normal(rng: &Rng) -> f64 {
+    let u1 = 1.0 - rng.f64(); // in (0, 1]
+    let u2 = rng.f64();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+// one bound is required for each gene
+fn check_bounds(len: usize, bounds: &[(f64, f64)]) -> Result<(), MincostError> {
+    if len == bounds.len() {
+        Ok(())
+    } else {
+        Err(MincostError::InvalidConfig(format!(
+            "{} bounds for {} genes, one bound is required for each gene",
+            bounds.len(),
+            len
+        )))
+    }
+}
+
+// panic unless there is one bound for each gene
+fn assert_bounds(len: usize, bounds: &[(f64, f64)]) {
+    check_bounds(len, bounds).unwrap_or_else(|e| panic!("{}", e));
+}
+
+fn clamp(value: f64, (lower, upper): (f64, f64)) -> f64 {
+    value.max(lower).min(upper)
+}
+
+/// Crossover and mutation operators for real-valued encoding.
+///
+/// Each of them panics if `bounds` does not hold one bound per gene.
+impl<T> Individual<T>
+where
+    T: Real,
+{
+    /// Simulated binary crossover with distribution index `eta`. Each gene
+    /// pair is recombined with 0.5 probability, the child takes either of
+    /// the two offspring genes.
+    pub fn simulated_binary_crossover(
+        &self,
+        another: &Self,
+        eta: f64,
+        bounds: &[(f64, f64)],
+        rng: &Rng,
+    ) -> Self {
+        assert_bounds(self.genes.len(), bounds);
+        let exponent = 1.0 / (eta + 1.0);
+        // spread factor of one offspring, bounded by `beta`
+        let spread = |beta: f64| {
+            let alpha = 2.0 - beta.powf(-(eta + 1.0));
+            let u = rng.f64();
+            if u <= 1.0 / alpha {
+                (u * alpha).powf(exponent)
+            } else {
+                (1.0 / (2.0 - u * alpha)).powf(exponent)
+            }
+        };
+        let genes = self
+            .genes
+            .iter()
+            .zip(another.genes.iter())
+            .zip(bounds.iter())
+            .map(|((x1, x2), &(lower, upper))| {
+                let (x1, x2) = (x1.to_f64(), x2.to_f64());
+                if rng.bool() || (x1 - x2).abs() < 1e-14 {
+                    return T::from_f64(clamp(x1, (lower, upper)));
+                }
+                let (y1, y2) = (x1.min(x2), x1.max(x2));
+                let child = if rng.bool() {
+                    let beta_q = spread(1.0 + 2.0 * (y1 - lower) / (y2 - y1));
+                    0.5 * ((y1 + y2) - beta_q * (y2 - y1))
+                } else {
+                    let beta_q = spread(1.0 + 2.0 * (upper - y2) / (y2 - y1));
+                    0.5 * ((y1 + y2) + beta_q * (y2 - y1))
+                };
+                T::from_f64(clamp(child, (lower, upper)))
+            })
+            .collect();
+        Individual { genes }
+    }
+    /// Blend crossover. Each child gene is drawn uniformly from the parents'
+    /// interval extended by `alpha` of its width on both sides.
+    pub fn blend_crossover(
+        &self,
+        another: &Self,
+        alpha: f64,
+        bounds: &[(f64, f64)],
+        rng: &Rng,
+    ) -> Self {
+        assert_bounds(self.genes.len(), bounds);
+        let genes = self
+            .genes
+            .iter()
+            .zip(another.genes.iter())
+            .zip(bounds.iter())
+            .map(|((x1, x2), bound)| {
+                let (x1, x2) = (x1.to_f64(), x2.to_f64());
+                let extent = alpha * (x1 - x2).abs();
+                let low = x1.min(x2) - extent;
+                let high = x1.max(x2) + extent;
+                T::from_f64(clamp(low + (high - low) * rng.f64(), *bound))
+            })
+            .collect();
+        Individual { genes }
+    }
+    /// Whole arithmetic crossover. The child is a random convex combination
+    /// of both parents.
+    pub fn arithmetic_crossover(&self, another: &Self, bounds: &[(f64, f64)], rng: &Rng) -> Self {
+        assert_bounds(self.genes.len(), bounds);
+        let lambda = rng.f64();
+        let genes = self
+            .genes
+            .iter()
+            .zip(another.genes.iter())
+            .zip(bounds.iter())
+            .map(|((x1, x2), bound)| {
+                let child = lambda * x1.to_f64() + (1.0 - lambda) * x2.to_f64();
+                T::from_f64(clamp(child, *bound))
+            })
+            .collect();
+        Individual { genes }
+    }
+    /// Polynomial mutation with distribution index `eta`, each gene is
+    /// mutated with probability `rate`.
+    pub fn polynomial_mutate(&mut self, eta: f64, rate: f32, bounds: &[(f64, f64)], rng: &Rng) {
+        assert_bounds(self.genes.len(), bounds);
+        let exponent = 1.0 / (eta + 1.0);
+        for (gene, &(lower, upper)) in self.genes.iter_mut().zip(bounds.iter()) {
+            if rng.f32() >= rate || upper <= lower {
+                continue;
+            }
+            let y = gene.to_f64();
+            let delta1 = (y - lower) / (upper - lower);
+            let delta2 = (upper - y) / (upper - lower);
+            let u = rng.f64();
+            let delta_q = if u < 0.5 {
+                let val = 2.0 * u + (1.0 - 2.0 * u) * (1.0 - delta1).powf(eta + 1.0);
+                val.powf(exponent) - 1.0
+            } else {
+                let val = 2.0 * (1.0 - u) + 2.0 * (u - 0.5) * (1.0 - delta2).powf(eta + 1.0);
+                1.0 - val.powf(exponent)
+            };
+            *gene = T::from_f64(clamp(y + delta_q * (upper - lower), (lower, upper)));
+        }
+    }
+    /// Gaussian mutation, each gene is mutated with probability `rate` by a
+    /// normal step whose standard deviation is `sigma` of the gene's range.
+    pub fn gaussian_mutate(&mut self, sigma: f64, rate: f32, bounds: &[(f64, f64)], rng: &Rng) {
+        assert_bounds(self.genes.len(), bounds);
+        for (gene, &(lower, upper)) in self.genes.iter_mut().zip(bounds.iter()) {
+            if rng.f32() >= rate {
+                continue;
+            }
+            let y = gene.to_f64() + sigma * (upper - lower) * standard_normal(rng);
+            *gene = T::from_f64(clamp(y, (lower, upper)));
+        }
+    }
+}
+
+impl<T> Crossover<T> for SimulatedBinaryCrossover
+where
+    T: Real,
+{
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        rng: &Rng,
+    ) -> Individual<T> {
+        parent1.simulated_binary_crossover(parent2, self.eta, &self.bounds, rng)
+    }
+    fn check(&self, len: usize) -> Result<(), MincostError> {
+        check_bounds(len, &self.bounds)
+    }
+}
+
+impl<T> Crossover<T> for BlendCrossover
+where
+    T: Real,
+{
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        rng: &Rng,
+    ) -> Individual<T> {
+        parent1.blend_crossover(parent2, self.alpha, &self.bounds, rng)
+    }
+    fn check(&self, len: usize) -> Result<(), MincostError> {
+        check_bounds(len, &self.bounds)
+    }
+}
+
+impl<T> Crossover<T> for ArithmeticCrossover
+where
+    T: Real,
+{
+    fn crossover(
+        &self,
+        parent1: &Individual<T>,
+        parent2: &Individual<T>,
+        rng: &Rng,
+    ) -> Individual<T> {
+        parent1.arithmetic_crossover(parent2, &self.bounds, rng)
+    }
+    fn check(&self, len: usize) -> Result<(), MincostError> {
+        check_bounds(len, &self.bounds)
+    }
+}
+
+impl<T> Mutation<T> for PolynomialMutation
+where
+    T: Real,
+{
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        individual.polynomial_mutate(self.eta, self.rate, &self.bounds, rng);
+    }
+    fn check(&self, len: usize) -> Result<(), MincostError> {
+        check_bounds(len, &self.bounds)
+    }
+}
+
+impl<T> Mutation<T> for GaussianMutation
+where
+    T: Real,
+{
+    fn mutate(&self, individual: &mut Individual<T>, rng: &Rng) {
+        individual.gaussian_mutate(self.sigma, self.rate, &self.bounds, rng);
+    }
+    fn check(&self, len: usize) -> Result<(), MincostError> {
+        check_bounds(len, &self.bounds)
+    }
+}
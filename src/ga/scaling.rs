@@ -0,0 +1,266 @@
+//! Fitness scaling
+//!
+//! A [`Scaling`] turns the scores of a population into non-negative weights
+//! before selection, so that proportional selection works with negative scores,
+//! scores summing to zero or scores too close to each other to tell apart. The
+//! order of scores is kept, except that [`SigmaTruncation`] and [`LinearScaling`]
+//! give no weight to the least scores, which tournament and rank based selections
+//! then see as ties.
+use std::collections::VecDeque;
+
+/// strategy to turn scores into non-negative selection weights
+pub trait Scaling {
+    /// weight of each individual, the larger score the larger weight
+    ///
+    /// `score` is the fitness oriented by [`Objective`](crate::Objective), an
+    /// individual of the worst possible fitness arrives as negative infinity and
+    /// gets no weight.
+    fn scale(&mut self, score: &[f64]) -> Vec<f64>;
+}
+
+/// Linear scaling.
+///
+/// Scores are shifted so the least one is 0, then mapped linearly so that the
+/// mean weight is kept and the fittest weighs `multiple` times the mean, unless
+/// the least fit would weigh below 0.
+#[derive(Debug, Copy, Clone)]
+pub struct LinearScaling {
+    /// weight of the fittest individual over the mean weight, greater than 1
+    pub multiple: f64,
+}
+
+/// Sigma truncation.
+///
+/// Scores are shifted by `c` standard deviations below the mean, and those
+/// falling below 0 get no weight.
+#[derive(Debug, Copy, Clone)]
+pub struct SigmaTruncation {
+    /// standard deviations kept below the mean, usually 1 to 3
+    pub c: f64,
+}
+
+/// Windowing.
+///
+/// Scores are shifted by the least score seen over the last `window` generations,
+/// so the pressure does not fade as the whole population improves. The window is
+/// not saved by `Evolution::checkpoint`, a resumed evolution starts with an empty one.
+#[derive(Debug, Clone)]
+pub struct WindowScaling {
+    window: usize,
+    least: VecDeque<f64>,
+}
+
+/// Boltzmann scaling.
+///
+/// Each weight is `exp(score / temperature)`, a low temperature favors the
+/// fittest individuals and a high one flattens the weights.
+#[derive(Debug, Copy, Clone)]
+pub struct BoltzmannScaling {
+    /// temperature, greater than 0
+    pub temperature: f64,
+}
+
+impl Default for LinearScaling {
+    fn default() -> Self {
+        LinearScaling { multiple: 2.0 }
+    }
+}
+
+impl Default for SigmaTruncation {
+    fn default() -> Self {
+        SigmaTruncation { c: 2.0 }
+    }
+}
+
+impl Default for BoltzmannScaling {
+    fn default() -> Self {
+        BoltzmannScaling { temperature: 1.0 }
+    }
+}
+
+impl WindowScaling {
+    /// windowing over the last `window` generations, at least 1
+    pub fn new(window: usize) -> Self {
+        WindowScaling {
+            window: window.max(1),
+            least: VecDeque::with_capacity(window.max(1)),
+        }
+    }
+}
+
+// least, greatest and mean of finite scores, `None` if no score is finite
+fn summary(score: &[f64]) -> Option<(f64, f64, f64)> {
+    let finite: Vec<f64> = score.iter().cloned().filter(|x| x.is_finite()).collect();
+    if finite.is_empty() {
+        return None;
+    }
+    let least = finite.iter().cloned().fold(f64::INFINITY, f64::min);
+    let greatest = finite.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let mean = finite.iter().sum::<f64>() / finite.len() as f64;
+    Some((least, greatest, mean))
+}
+
+// weight of each finite score by `f`, clipped at 0, other scores get no weight
+fn weigh(score: &[f64], f: impl Fn(f64) -> f64) -> Vec<f64> {
+    score
+        .iter()
+        .map(|x| if x.is_finite() { f(*x).max(0.0) } else { 0.0 })
+        .collect()
+}
+
+impl Scaling for LinearScaling {
+    fn scale(&mut self, score: &[f64]) -> Vec<f64> {
+        let (least, greatest, mean) = match summary(score) {
+            Some(summary) => summary,
+            None => return vec![0.0; score.len()],
+        };
+        // on scores shifted to a least one of 0
+        let (max, avg) = (greatest - least, mean - least);
+        if max - avg <= f64::EPSILON * max {
+            // all scores are equal
+            return weigh(score, |_| 1.0);
+        }
+        if max > self.multiple * avg {
+            let a = (self.multiple - 1.0) * avg / (max - avg);
+            let b = avg * (max - self.multiple * avg) / (max - avg);
+            weigh(score, |x| a * (x - least) + b)
+        } else {
+            // the least fit would weigh below 0, so it weighs 0 instead
+            weigh(score, |x| x - least)
+        }
+    }
+}
+
+impl Scaling for SigmaTruncation {
+    fn scale(&mut self, score: &[f64]) -> Vec<f64> {
+        let (_, _, mean) = match summary(score) {
+            Some(summary) => summary,
+            None => return vec![0.0; score.len()],
+        };
+        let finite = score.iter().filter(|x| x.is_finite());
+        let n = finite.clone().count() as f64;
+        let sigma = (finite.map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+        weigh(score, |x| x - (mean - self.c * sigma))
+    }
+}
+
+impl Scaling for WindowScaling {
+    fn scale(&mut self, score: &[f64]) -> Vec<f64> {
+        let (least, _, _) = match summary(score) {
+            Some(summary) => summary,
+            None => return vec![0.0; score.len()],
+        };
+        if self.least.len() == self.window {
+            self.least.pop_front();
+        }
+        self.least.push_back(least);
+        let baseline = self.least.iter().cloned().fold(f64::INFINITY, f64::min);
+        weigh(score, |x| x - baseline)
+    }
+}
+
+impl Scaling for BoltzmannScaling {
+    fn scale(&mut self, score: &[f64]) -> Vec<f64> {
+        let (_, greatest, _) = match summary(score) {
+            Some(summary) => summary,
+            None => return vec![0.0; score.len()],
+        };
+        // shifted by the greatest score to keep the exponential finite
+        weigh(score, |x| ((x - greatest) / self.temperature).exp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rng, RouletteSelection, Selection};
+
+    fn mean(weight: &[f64]) -> f64 {
+        weight.iter().sum::<f64>() / weight.len() as f64
+    }
+
+    fn greatest(weight: &[f64]) -> f64 {
+        weight.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    #[test]
+    fn linear_keeps_mean_and_weighs_fittest_multiple_of_it() {
+        let score = [-3.0, -1.0, 0.0, 4.0];
+        let weight = LinearScaling { multiple: 2.0 }.scale(&score);
+        // mean of scores shifted to a least one of 0
+        assert!((mean(&weight) - 3.0).abs() < 1e-12);
+        assert!((greatest(&weight) - 2.0 * mean(&weight)).abs() < 1e-12);
+        assert!(weight.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn linear_gives_least_no_weight_when_it_would_weigh_below_zero() {
+        let weight = LinearScaling { multiple: 2.0 }.scale(&[0.0, 9.0, 10.0]);
+        assert_eq!(weight, vec![0.0, 9.0, 10.0]);
+        let weight = LinearScaling::default().scale(&[-1.0, -1.0, f64::NEG_INFINITY]);
+        assert_eq!(weight, vec![1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn sigma_truncation_gives_no_weight_below_baseline() {
+        // mean 4 and standard deviation of 10.0f64.sqrt()
+        let score = [1.0, 2.0, 3.0, 4.0, 10.0];
+        let weight = SigmaTruncation { c: 1.0 }.scale(&score);
+        let baseline = 4.0 - 10.0f64.sqrt();
+        for (w, x) in weight.iter().zip(score.iter()) {
+            assert!((w - (x - baseline)).abs() < 1e-12);
+        }
+        let weight = SigmaTruncation { c: 0.5 }.scale(&score);
+        assert_eq!(&weight[..2], &[0.0, 0.0]);
+        assert!(weight[2] > 0.0 && weight[3] > weight[2]);
+    }
+
+    #[test]
+    fn window_shifts_by_least_score_of_last_generations() {
+        let mut scaling = WindowScaling::new(2);
+        assert_eq!(scaling.scale(&[0.0, 1.0]), vec![0.0, 1.0]);
+        assert_eq!(scaling.scale(&[5.0, 6.0]), vec![5.0, 6.0]);
+        // the least score 0 of the first generation left the window
+        assert_eq!(scaling.scale(&[7.0, 8.0]), vec![2.0, 3.0]);
+    }
+
+    #[test]
+    fn boltzmann_weighs_exponentially_by_temperature() {
+        let score = [-2.0, 0.0, 1.0, f64::NEG_INFINITY];
+        let weight = BoltzmannScaling { temperature: 2.0 }.scale(&score);
+        assert_eq!(weight[2], 1.0);
+        assert!((weight[0] - (-1.5f64).exp()).abs() < 1e-12);
+        assert!((weight[1] - (-0.5f64).exp()).abs() < 1e-12);
+        assert_eq!(weight[3], 0.0);
+    }
+
+    #[test]
+    fn no_finite_score_gives_no_weight() {
+        let score = [f64::NEG_INFINITY; 3];
+        assert_eq!(LinearScaling::default().scale(&score), vec![0.0; 3]);
+        assert_eq!(SigmaTruncation::default().scale(&score), vec![0.0; 3]);
+        assert_eq!(WindowScaling::new(3).scale(&score), vec![0.0; 3]);
+        assert_eq!(BoltzmannScaling::default().scale(&score), vec![0.0; 3]);
+    }
+
+    #[test]
+    fn roulette_favors_fittest_of_negative_and_zero_sum_scores() {
+        let rng = Rng::with_seed(2);
+        for score in [[-4.0, -3.0, -2.0, -1.0], [-3.0, -1.0, 1.0, 3.0]] {
+            let schemes: Vec<Box<dyn Scaling>> = vec![
+                Box::new(LinearScaling::default()),
+                Box::new(SigmaTruncation::default()),
+                Box::new(WindowScaling::new(3)),
+                Box::new(BoltzmannScaling::default()),
+            ];
+            for mut scaling in schemes {
+                let weight = scaling.scale(&score);
+                let mut picked = [0; 4];
+                for i in RouletteSelection.select(&weight, 4000, &rng) {
+                    picked[i] += 1;
+                }
+                assert!(picked.windows(2).all(|p| p[0] < p[1]), "{:?}", picked);
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Selection operators
+//!
+//! A [`Selection`] picks the parents of the next generation by index, from the
+//! scores of the ranked population. Roulette wheel and stochastic universal
+//! sampling pick in proportion to scores, while tournament, rank and truncation
+//! selection only depend on their order.
+use crate::Rng;
+
+/// strategy to pick parents from population
+pub trait Selection {
+    /// pick `count` individuals by index, the larger score the fitter,
+    /// drawing randomness from `rng`
+    ///
+    /// `score` is the fitness oriented by [`Objective`](crate::Objective),
+    /// so a minimized fitness arrives negated.
+    fn select(&self, score: &[f64], count: usize, rng: &Rng) -> Vec<usize>;
+}
+
+/// Roulette wheel selection, the default selection method.
+///
+/// Each individual is picked with probability proportional to its score.
+/// Scores are shifted by the least one when any of them is negative.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RouletteSelection;
+
+/// Stochastic universal sampling.
+///
+/// Like roulette wheel selection, but all individuals are picked by evenly
+/// spaced pointers from a single spin, which keeps the spread low.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct StochasticUniversalSampling;
+
+/// Tournament selection.
+///
+/// The fittest of `size` randomly drawn individuals wins each tournament.
+#[derive(Debug, Copy, Clone)]
+pub struct TournamentSelection {
+    /// individuals taking part in each tournament, at least 1
+    pub size: usize,
+}
+
+/// Linear rank selection.
+///
+/// Individuals are picked by their rank only, the fittest one with
+/// `pressure / n` probability and the least fit with `(2 - pressure) / n`.
+#[derive(Debug, Copy, Clone)]
+pub struct RankSelection {
+    /// selection pressure, in 1 to 2
+    pub pressure: f64,
+}
+
+/// Truncation selection.
+///
+/// Individuals are picked uniformly from the fittest `proportion` of population.
+#[derive(Debug, Copy, Clone)]
+pub struct TruncationSelection {
+    /// proportion of population allowed to breed, in 0 to 1
+    pub proportion: f64,
+}
+
+impl Default for TournamentSelection {
+    fn default() -> Self {
+        TournamentSelection { size: 2 }
+    }
+}
+
+impl Default for RankSelection {
+    fn default() -> Self {
+        RankSelection { pressure: 1.5 }
+    }
+}
+
+impl Default for TruncationSelection {
+    fn default() -> Self {
+        TruncationSelection { proportion: 0.5 }
+    }
+}
+
+// pick index of the first cumulative weight beyond point
+fn locate(cum_weight: &[f64], point: f64) -> usize {
+    cum_weight
+        .iter()
+        .position(|w| point < *w)
+        .unwrap_or(cum_weight.len() - 1)
+}
+
+// weights of proportional selection, shifted to be non-negative, an individual of
+// the worst possible fitness has no weight
+fn proportion(score: &[f64]) -> Vec<f64> {
+    let least = score
+        .iter()
+        .cloned()
+        .filter(|x| x.is_finite())
+        .fold(f64::INFINITY, f64::min);
+    let shift = if least < 0.0 { least } else { 0.0 };
+    score
+        .iter()
+        .map(|x| if x.is_finite() { x - shift } else { 0.0 })
+        .collect()
+}
+
+// cumulative weights, `None` when they can not form a wheel
+fn cumulate(weight: &[f64]) -> Option<Vec<f64>> {
+    let mut tmp = 0.0;
+    let cum_weight: Vec<f64> = weight
+        .iter()
+        .map(|w| {
+            tmp += w;
+            tmp
+        })
+        .collect();
+    if tmp > 0.0 && tmp.is_finite() && weight.iter().all(|w| *w >= 0.0) {
+        Some(cum_weight)
+    } else {
+        None
+    }
+}
+
+// indices sorted from the least fit to the fittest
+fn ascending(score: &[f64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..score.len()).collect();
+    order.sort_by(|a, b| {
+        score[*a]
+            .partial_cmp(&score[*b])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    order
+}
+
+impl Selection for RouletteSelection {
+    fn select(&self, score: &[f64], count: usize, rng: &Rng) -> Vec<usize> {
+        match cumulate(&proportion(score)) {
+            Some(cum_score) => {
+                let total = cum_score[cum_score.len() - 1];
+                (0..count)
+                    .map(|_| locate(&cum_score, total * rng.f64()))
+                    .collect()
+            }
+            // no wheel can be formed, fall back to uniform selection
+            None => (0..count).map(|_| rng.usize(..score.len())).collect(),
+        }
+    }
+}
+
+impl Selection for StochasticUniversalSampling {
+    fn select(&self, score: &[f64], count: usize, rng: &Rng) -> Vec<usize> {
+        match cumulate(&proportion(score)) {
+            Some(cum_score) => {
+                let total = cum_score[cum_score.len() - 1];
+                let distance = total / count as f64;
+                let start = distance * rng.f64();
+                (0..count)
+                    .map(|i| locate(&cum_score, start + i as f64 * distance))
+                    .collect()
+            }
+            // no wheel can be formed, fall back to uniform selection
+            None => (0..count).map(|_| rng.usize(..score.len())).collect(),
+        }
+    }
+}
+
+impl Selection for TournamentSelection {
+    fn select(&self, score: &[f64], count: usize, rng: &Rng) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                let mut winner = rng.usize(..score.len());
+                for _ in 1..self.size {
+                    let challenger = rng.usize(..score.len());
+                    if score[challenger] > score[winner] {
+                        winner = challenger;
+                    }
+                }
+                winner
+            })
+            .collect()
+    }
+}
+
+impl Selection for RankSelection {
+    fn select(&self, score: &[f64], count: usize, rng: &Rng) -> Vec<usize> {
+        let n = score.len() as f64;
+        let order = ascending(score);
+        // linear weight on rank, the least fit has rank 0
+        let weight: Vec<f64> = (0..order.len())
+            .map(|rank| {
+                let slope = if n > 1.0 {
+                    rank as f64 / (n - 1.0)
+                } else {
+                    0.0
+                };
+                (2.0 - self.pressure + 2.0 * (self.pressure - 1.0) * slope) / n
+            })
+            .collect();
+        match cumulate(&weight) {
+            Some(cum_weight) => {
+                let total = cum_weight[cum_weight.len() - 1];
+                (0..count)
+                    .map(|_| order[locate(&cum_weight, total * rng.f64())])
+                    .collect()
+            }
+            None => (0..count).map(|_| rng.usize(..score.len())).collect(),
+        }
+    }
+}
+
+impl Selection for TruncationSelection {
+    fn select(&self, score: &[f64], count: usize, rng: &Rng) -> Vec<usize> {
+        let order = ascending(score);
+        let kept = ((self.proportion * order.len() as f64).ceil() as usize).clamp(1, order.len());
+        let fittest = &order[order.len() - kept..];
+        (0..count)
+            .map(|_| fittest[rng.usize(..fittest.len())])
+            .collect()
+    }
+}
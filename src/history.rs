@@ -0,0 +1,160 @@
+//! Statistics of a run
+//!
+//! Every optimizer records an [`IterationStats`] per iteration into its
+//! [`RunHistory`], which can be inspected after the run or exported as CSV to
+//! plot the convergence.
+//! ```ignore
+//! let best_ind = evolution.evolute();
+//! evolution.history().write_csv(std::fs::File::create("history.csv")?)?;
+//! ```
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+use crate::Objective;
+use std::io::{self, Write};
+
+/// cost statistics of one iteration
+///
+/// Statistics are taken over the population of GA and PSO, or over the
+/// neighbors evaluated at the iteration of methods keeping a single solution.
+/// Iteration 0 describes the initial solutions. Only finite costs count, so a
+/// solution ranked worst by [`NanPolicy::Worst`](crate::NanPolicy::Worst) does
+/// not show up, and the statistics are NaN if no cost is finite.
+#[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IterationStats {
+    /// iterations done, ie. generations, temperature levels or moves
+    pub iteration: usize,
+    /// fitness evaluations spent so far
+    pub evaluations: usize,
+    /// best cost of the iteration
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite"))]
+    pub best: f64,
+    /// mean cost of the iteration
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite"))]
+    pub mean: f64,
+    /// worst cost of the iteration
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite"))]
+    pub worst: f64,
+    /// standard deviation of costs of the iteration
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite"))]
+    pub std_dev: f64,
+    /// best cost found so far
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite"))]
+    pub best_so_far: f64,
+    /// diversity of the population, `None` for methods keeping a single solution
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite::option"))]
+    pub diversity: Option<f64>,
+    /// temperature of the level, annealing only
+    pub temperature: Option<f32>,
+}
+
+/// statistics of every iteration of a run, in order
+#[derive(Debug, Default, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunHistory {
+    records: Vec<IterationStats>,
+}
+
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+impl IterationStats {
+    // statistics of finite costs evaluated at the iteration, without diversity nor
+    // temperature; the worst possible fitness kept by `NanPolicy::Worst` is left out
+    pub(crate) fn new(
+        iteration: usize,
+        evaluations: usize,
+        costs: &[f64],
+        best_so_far: f64,
+        objective: Objective,
+    ) -> Self {
+        let costs: Vec<f64> = costs.iter().copied().filter(|c| c.is_finite()).collect();
+        let n = costs.len() as f64;
+        let mean = costs.iter().sum::<f64>() / n;
+        let variance = costs.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / n;
+        // no finite cost is evaluated at the iteration if `costs` is empty
+        let mut best = costs.first().copied().unwrap_or(f64::NAN);
+        let mut worst = best;
+        for c in costs.iter().skip(1) {
+            if objective.is_better(c, &best) {
+                best = *c;
+            }
+            if objective.is_better(&worst, c) {
+                worst = *c;
+            }
+        }
+        IterationStats {
+            iteration,
+            evaluations,
+            best,
+            mean,
+            worst,
+            std_dev: variance.sqrt(),
+            best_so_far,
+            diversity: None,
+            temperature: None,
+        }
+    }
+}
+
+impl RunHistory {
+    /// statistics of every iteration recorded so far
+    pub fn records(&self) -> &[IterationStats] {
+        &self.records
+    }
+    /// statistics of the last iteration, `None` if nothing is recorded
+    pub fn last(&self) -> Option<&IterationStats> {
+        self.records.last()
+    }
+    /// write as CSV with a header line, missing values are left empty
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "iteration,evaluations,best,mean,worst,std_dev,best_so_far,diversity,temperature"
+        )?;
+        for r in self.records.iter() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{}",
+                r.iteration,
+                r.evaluations,
+                r.best,
+                r.mean,
+                r.worst,
+                r.std_dev,
+                r.best_so_far,
+                r.diversity.map_or(String::new(), |d| d.to_string()),
+                r.temperature.map_or(String::new(), |t| t.to_string()),
+            )?;
+        }
+        Ok(())
+    }
+    /// CSV with a header line, missing values are left empty
+    pub fn to_csv(&self) -> String {
+        let mut csv = Vec::new();
+        self.write_csv(&mut csv)
+            .expect("writing to memory never fails");
+        String::from_utf8(csv).expect("CSV is valid UTF-8")
+    }
+    #[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+    pub(crate) fn push(&mut self, stats: IterationStats) {
+        self.records.push(stats);
+    }
+}
+
+#[cfg(test)]
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worst_fitness_is_left_out_of_stats() {
+        let costs = [1.0, f64::NEG_INFINITY, 3.0];
+        let stats = IterationStats::new(1, 3, &costs, 3.0, Objective::Maximize);
+        assert_eq!((stats.best, stats.worst), (3.0, 1.0));
+        assert_eq!((stats.mean, stats.std_dev), (2.0, 1.0));
+    }
+
+    #[test]
+    fn stats_without_finite_cost_are_nan() {
+        let stats = IterationStats::new(1, 1, &[f64::INFINITY], 0.0, Objective::Minimize);
+        assert!(stats.best.is_nan() && stats.mean.is_nan() && stats.std_dev.is_nan());
+    }
+}
@@ -1,9 +1,10 @@
 //! A collection of modern heuristic optimization toolkit.
 //!
-//! There are tree common optimization methods in the crate currently.
+//! There are four common optimization methods in the crate currently.
 //! 1.genetic algorithm
 //! 2.simluated annealing
 //! 3.particle swarm optimization
+//! 4.tabu search
 //!
 //! You can fit any of these methods into your project by enabling relavant features
 
@@ -25,12 +26,64 @@
 //! mincost = { version = "0.1.1", features = ["pso"] }
 //! ```
 
+//! To use tabu search
+//! ```toml
+//! [dependencies]
+//! mincost = { version = "0.1.1", features = ["tabu"] }
+//! ```
+
+//! To evaluate populations and swarms concurrently, the fitness closure being `Sync`
+//! ```toml
+//! [dependencies]
+//! mincost = { version = "0.1.1", features = ["ga", "parallel"] }
+//! ```
+
+//! To checkpoint and resume optimizers by serde
+//! ```toml
+//! [dependencies]
+//! mincost = { version = "0.1.1", features = ["ga", "serde"] }
+//! ```
+
+//! Operators, cooling schedules, fitness scaling and termination criteria are
+//! strategy types behind a trait each, chosen per optimizer at runtime by its
+//! `with_*` methods, ie. `Evolution::with_crossover`. Implement the trait to plug
+//! in your own. Checkpoints only save the data of a run, so set the strategies
+//! again on a resumed optimizer.
+
+mod error;
+mod history;
+mod nan_policy;
+#[cfg(feature = "serde")]
+mod non_finite;
+mod objective;
+mod observer;
+mod optimizer;
+mod parallel;
+mod problem;
+mod termination;
+#[cfg(test)]
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+mod testing;
+
 #[cfg(feature = "ga")]
 mod ga;
 #[cfg(feature = "pso")]
 mod pso;
 #[cfg(feature = "sa")]
 mod sa;
+#[cfg(feature = "tabu")]
+mod tabu;
+
+pub use error::MincostError;
+pub use fastrand::Rng;
+pub use history::*;
+pub use nan_policy::NanPolicy;
+pub use objective::*;
+pub use observer::*;
+pub use optimizer::*;
+pub use parallel::MaybeSync;
+pub use problem::*;
+pub use termination::*;
 
 #[cfg(feature = "ga")]
 pub use ga::*;
@@ -40,3 +93,6 @@ pub use sa::*;
 
 #[cfg(feature = "pso")]
 pub use pso::*;
+
+#[cfg(feature = "tabu")]
+pub use tabu::*;
@@ -0,0 +1,74 @@
+//! Handling of invalid fitness
+//!
+//! A fitness closure may return NaN or infinity, ie. from a failed simulation.
+//! The [`NanPolicy`] in the config of an optimizer decides what becomes of such
+//! a solution, the same way in GA ranking, SA acceptance, PSO best updates and
+//! tabu moves.
+//! ```ignore
+//! let config = EvolutionConfig::builder(100, 50)
+//!     .nan_policy(NanPolicy::Resample(10))
+//!     .build()?;
+//! ```
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+use crate::{MincostError, Objective};
+
+/// what becomes of a solution whose fitness is NaN or infinite
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NanPolicy {
+    /// abort the run with [`MincostError::InvalidFitness`](crate::MincostError::InvalidFitness)
+    #[default]
+    Error,
+    /// keep the solution with the worst possible fitness, so it is ranked last,
+    /// never accepted and never the best
+    Worst,
+    /// reject the solution and draw another one, up to the number of attempts
+    /// before aborting with [`MincostError::InvalidFitness`](crate::MincostError::InvalidFitness)
+    ///
+    /// A new solution is drawn from the randomness of the optimizer at
+    /// initialization, then the child is mutated again in GA, another neighbor is
+    /// drawn in SA, the particle moves again from its previous position, without
+    /// momentum, in PSO, and the neighbor is not admissible in tabu search, where the
+    /// attempts count the neighbors rejected at a move.
+    Resample(usize),
+}
+
+impl NanPolicy {
+    #[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+    // fitness kept for the evaluated cost, `None` to draw another solution at the
+    // `attempt`-th redraw
+    pub(crate) fn apply(
+        self,
+        cost: f64,
+        objective: Objective,
+        attempt: usize,
+    ) -> Result<Option<f64>, MincostError> {
+        if cost.is_finite() {
+            return Ok(Some(cost));
+        }
+        match self {
+            NanPolicy::Error => Err(MincostError::InvalidFitness(cost)),
+            NanPolicy::Worst => Ok(Some(objective.worst())),
+            NanPolicy::Resample(attempts) if attempt < attempts => Ok(None),
+            NanPolicy::Resample(_) => Err(MincostError::InvalidFitness(cost)),
+        }
+    }
+    // draw solutions until the fitness of one is kept, returns the solution, its
+    // fitness and the number of evaluations, for the methods drawing one solution at a time
+    #[cfg(any(feature = "sa", feature = "tabu"))]
+    pub(crate) fn draw<S>(
+        self,
+        objective: Objective,
+        mut draw: impl FnMut() -> S,
+        fitness: impl Fn(&S) -> f64,
+    ) -> Result<(S, f64, usize), MincostError> {
+        let mut attempt = 0;
+        loop {
+            let solution = draw();
+            if let Some(cost) = self.apply(fitness(&solution), objective, attempt)? {
+                return Ok((solution, cost, attempt + 1));
+            }
+            attempt += 1;
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Serde of costs which may be NaN or infinite
+//!
+//! Text formats like JSON have no number for NaN nor infinity, so such a cost is
+//! written as a string, ie. `"inf"`, `"-inf"` or `"NaN"`, and read back as the same
+//! float. Binary formats write every cost as a float. Used by cost fields through
+//! `#[serde(with = "crate::non_finite")]`, or one of the submodules for options and
+//! vectors of costs.
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+
+// a cost written as a string in text formats when it is not finite
+struct Cost(f64);
+
+impl Serialize for Cost {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.is_finite() || !serializer.is_human_readable() {
+            serializer.serialize_f64(self.0)
+        } else {
+            serializer.collect_str(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Cost {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(CostVisitor)
+        } else {
+            deserializer.deserialize_f64(CostVisitor)
+        }
+    }
+}
+
+struct CostVisitor;
+
+impl<'de> Visitor<'de> for CostVisitor {
+    type Value = Cost;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a float, or \"inf\", \"-inf\" or \"NaN\"")
+    }
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Cost, E> {
+        Ok(Cost(v))
+    }
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Cost, E> {
+        Ok(Cost(v as f64))
+    }
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Cost, E> {
+        Ok(Cost(v as f64))
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Cost, E> {
+        v.parse()
+            .map(Cost)
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+pub(crate) fn serialize<S: Serializer>(cost: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    Cost(*cost).serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    Cost::deserialize(deserializer).map(|c| c.0)
+}
+
+// cost which may be missing
+pub(crate) mod option {
+    use super::Cost;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        cost: &Option<f64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        cost.map(Cost).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<f64>, D::Error> {
+        Option::<Cost>::deserialize(deserializer).map(|c| c.map(|c| c.0))
+    }
+}
+
+// costs of a swarm
+#[cfg(feature = "pso")]
+pub(crate) mod vec {
+    use super::Cost;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        costs: &[f64],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(costs.iter().map(|c| Cost(*c)))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<f64>, D::Error> {
+        let costs = Vec::<Cost>::deserialize(deserializer)?;
+        Ok(costs.into_iter().map(|c| c.0).collect())
+    }
+}
+
+// costs of a population, missing for individuals not evaluated yet
+#[cfg(feature = "ga")]
+pub(crate) mod vec_option {
+    use super::Cost;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        costs: &[Option<f64>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(costs.iter().map(|c| c.map(Cost)))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Option<f64>>, D::Error> {
+        let costs = Vec::<Option<Cost>>::deserialize(deserializer)?;
+        Ok(costs.into_iter().map(|c| c.map(|c| c.0)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Costs {
+        #[serde(with = "super")]
+        cost: f64,
+        #[serde(with = "super::option")]
+        diversity: Option<f64>,
+        #[serde(with = "super::option")]
+        missing: Option<f64>,
+    }
+
+    #[test]
+    fn non_finite_costs_round_trip_through_json() {
+        let costs = Costs {
+            cost: f64::NEG_INFINITY,
+            diversity: Some(f64::NAN),
+            missing: None,
+        };
+        let json = serde_json::to_string(&costs).unwrap();
+        assert_eq!(json, r#"{"cost":"-inf","diversity":"NaN","missing":null}"#);
+        let read: Costs = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.cost, f64::NEG_INFINITY);
+        assert!(read.diversity.unwrap().is_nan());
+        assert_eq!(read.missing, None);
+        let finite: Costs =
+            serde_json::from_str(r#"{"cost":2,"diversity":0.5,"missing":1e3}"#).unwrap();
+        assert_eq!(
+            (finite.cost, finite.diversity, finite.missing),
+            (2.0, Some(0.5), Some(1000.0))
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Optimization direction shared by all methods
+use std::cmp::Ordering;
+
+/// direction to optimize the fitness toward
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Objective {
+    /// the smaller fitness the better, ie. cost
+    #[default]
+    Minimize,
+    /// the larger fitness the better, ie. score
+    Maximize,
+}
+
+impl Objective {
+    /// whether fitness `a` is strictly better than fitness `b`
+    pub fn is_better<O: PartialOrd>(self, a: &O, b: &O) -> bool {
+        match self {
+            Objective::Minimize => a < b,
+            Objective::Maximize => a > b,
+        }
+    }
+    /// ordering between two fitness, the better one first
+    pub fn ordering<O: PartialOrd>(self, a: &O, b: &O) -> Option<Ordering> {
+        match self {
+            Objective::Minimize => a.partial_cmp(b),
+            Objective::Maximize => b.partial_cmp(a),
+        }
+    }
+    /// the worst possible fitness, infinity when minimized
+    pub fn worst(self) -> f64 {
+        match self {
+            Objective::Minimize => f64::INFINITY,
+            Objective::Maximize => f64::NEG_INFINITY,
+        }
+    }
+    /// fitness turned into a score, the larger score the better
+    pub fn score(self, fitness: f64) -> f64 {
+        match self {
+            Objective::Minimize => -fitness,
+            Objective::Maximize => fitness,
+        }
+    }
+}
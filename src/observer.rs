@@ -0,0 +1,61 @@
+//! Observers of a run
+//!
+//! An [`Observer`] is invoked after every iteration with a read-only
+//! [`Snapshot`] of the optimizer, and may ask the run to stop early. Any
+//! closure taking a snapshot and returning a [`Control`] is an observer.
+//! ```ignore
+//! let mut evolution = Evolution::init(config, fitness, randness).with_observer(
+//!     |snapshot: &Snapshot<usize>| {
+//!         println!("{}: {}", snapshot.progress.iteration, snapshot.progress.best_cost);
+//!         if aborted() { Control::Stop } else { Control::Continue }
+//!     },
+//! );
+//! ```
+use crate::{IterationStats, Progress};
+
+/// decision of an observer after an iteration
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Control {
+    /// keep running
+    Continue,
+    /// stop the run, reported as [`StopReason::Observer`](crate::StopReason::Observer)
+    Stop,
+}
+
+/// read-only view of an optimizer after an iteration
+#[derive(Debug)]
+pub struct Snapshot<'a, T> {
+    /// progress of the run so far
+    pub progress: Progress,
+    /// statistics of the iteration
+    pub stats: IterationStats,
+    /// genes of the best solution found so far
+    pub best: &'a [T],
+    /// genes of the population, or of the current solution for methods keeping a single one
+    pub population: Vec<&'a [T]>,
+}
+
+/// hook invoked after every iteration
+pub trait Observer<T> {
+    /// look at the optimizer, and decide whether the run goes on
+    fn observe(&mut self, snapshot: &Snapshot<T>) -> Control;
+}
+
+impl<T, C> Observer<T> for C
+where
+    C: FnMut(&Snapshot<T>) -> Control,
+{
+    fn observe(&mut self, snapshot: &Snapshot<T>) -> Control {
+        self(snapshot)
+    }
+}
+
+// show the snapshot to every observer, true if any of them asks to stop
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+pub(crate) fn notify<T>(observers: &mut [Box<dyn Observer<T>>], snapshot: &Snapshot<T>) -> bool {
+    let mut stop = false;
+    for observer in observers.iter_mut() {
+        stop |= observer.observe(snapshot) == Control::Stop;
+    }
+    stop
+}
@@ -0,0 +1,38 @@
+//! Common interface of optimizers
+//!
+//! Every optimizer in the crate implements [`Optimizer`] over the genes of its
+//! solution, so code driving one method can drive another without rewriting.
+//! ```ignore
+//! fn solve<O: Optimizer<usize>>(optimizer: &mut O, iteration: usize) -> Vec<usize> {
+//!     for _ in 0..iteration {
+//!         if !optimizer.step() {
+//!             break;
+//!         }
+//!         println!("best cost so far: {}", optimizer.best_cost());
+//!     }
+//!     optimizer.best().to_vec()
+//! }
+//! ```
+use crate::{MincostError, RunHistory, StopReason};
+
+/// optimizer searching solutions encoded by genes of `T`
+pub trait Optimizer<T> {
+    /// advance one iteration, false if the optimizer can not move any further or an
+    /// observer asked to stop; panics on the errors of [`Optimizer::try_step`]
+    fn step(&mut self) -> bool {
+        self.try_step().unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// advance one iteration as [`Optimizer::step`], reporting an invalid fitness,
+    /// genome or config as error instead of panicking
+    fn try_step(&mut self) -> Result<bool, MincostError>;
+    /// iterate until the termination fires, returns genes of the best solution found
+    fn run(&mut self) -> Vec<T>;
+    /// genes of the best solution found so far
+    fn best(&self) -> &[T];
+    /// fitness of the best solution found so far
+    fn best_cost(&self) -> f64;
+    /// criterion which stopped the last run, `None` if it has not stopped yet
+    fn stop_reason(&self) -> Option<&StopReason>;
+    /// statistics of every iteration so far
+    fn history(&self) -> &RunHistory;
+}
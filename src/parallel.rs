@@ -0,0 +1,57 @@
+//! Fitness evaluation over whole populations
+//!
+//! With the `parallel` feature, populations are evaluated concurrently on
+//! scoped threads, which requires the fitness closure and the genes to be
+//! `Sync`. Without it, evaluation stays on the calling thread and no extra
+//! bound is required.
+
+/// `Sync` with the `parallel` feature, implemented by every type otherwise
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+
+/// `Sync` with the `parallel` feature, implemented by every type otherwise
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSync for T {}
+
+// cost of each item, in the order of items, for the population based methods
+#[cfg(all(not(feature = "parallel"), any(feature = "ga", feature = "pso")))]
+pub(crate) fn evaluate<X, F>(items: &[&X], cost: &F) -> Vec<f64>
+where
+    X: MaybeSync + ?Sized,
+    F: Fn(&X) -> f64 + MaybeSync,
+{
+    items.iter().map(|x| cost(x)).collect()
+}
+
+// cost of each item, in the order of items, items are split evenly among threads
+#[cfg(all(feature = "parallel", any(feature = "ga", feature = "pso")))]
+pub(crate) fn evaluate<X, F>(items: &[&X], cost: &F) -> Vec<f64>
+where
+    X: MaybeSync + ?Sized,
+    F: Fn(&X) -> f64 + MaybeSync,
+{
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(|x| cost(x)).collect();
+    }
+    let chunk_size = items.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(|x| cost(x)).collect::<Vec<f64>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                // propagate panic of fitness closure to the caller
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect()
+    })
+}
@@ -0,0 +1,66 @@
+//! Problem description shared by all methods
+//!
+//! A [`Problem`] bundles the encoding, the fitness and the direction of an
+//! optimization problem, so the same problem can be handed to any optimizer
+//! by its `from_problem` constructor instead of separate closures.
+//! ```ignore
+//! struct Tsp {
+//!     distance: Vec<Vec<f64>>,
+//! }
+//!
+//! impl Problem<usize> for Tsp {
+//!     fn random(&self, rng: &Rng) -> Vec<usize> {
+//!         let mut route: Vec<usize> = (0..self.distance.len()).collect();
+//!         rng.shuffle(&mut route);
+//!         route
+//!     }
+//!     fn cost(&self, route: &[usize]) -> f64 {
+//!         route.windows(2).map(|w| self.distance[w[0]][w[1]]).sum()
+//!     }
+//! }
+//!
+//! let mut annealer = Annealer::from_problem(config, Tsp { distance });
+//! ```
+use crate::{Objective, Rng};
+
+/// optimization problem over solutions encoded by genes of `T`
+pub trait Problem<T: Clone> {
+    /// genes of a random solution
+    fn random(&self, rng: &Rng) -> Vec<T>;
+    /// fitness of the solution
+    fn cost(&self, genes: &[T]) -> f64;
+    /// whether to minimize or maximize the fitness, minimize by default
+    fn objective(&self) -> Objective {
+        Objective::Minimize
+    }
+    /// `(lower, upper)` bound of each gene, unbounded by default
+    ///
+    /// Only PSO uses the bounds, to draw initial velocities and keep particles
+    /// within them. GA and SA search through `random`, `mutate` and `neighbor`,
+    /// which are expected to stay within the bounds themselves.
+    fn bounds(&self) -> Option<Vec<(f64, f64)>> {
+        None
+    }
+    /// mutate the solution in place, swapping two random genes by default
+    fn mutate(&self, genes: &mut [T], rng: &Rng) {
+        if genes.is_empty() {
+            return;
+        }
+        let idx1 = rng.usize(..genes.len());
+        let idx2 = rng.usize(..genes.len());
+        genes.swap(idx1, idx2);
+    }
+    /// neighbor of the solution, a mutated copy by default
+    fn neighbor(&self, genes: &[T], rng: &Rng) -> Vec<T> {
+        let mut neighbor = genes.to_vec();
+        self.mutate(&mut neighbor, rng);
+        neighbor
+    }
+}
+
+/// boxed fitness closure of optimizers built from a [`Problem`]
+#[cfg(feature = "parallel")]
+pub type DynFitness<X> = Box<dyn Fn(&X) -> f64 + Sync>;
+/// boxed fitness closure of optimizers built from a [`Problem`]
+#[cfg(not(feature = "parallel"))]
+pub type DynFitness<X> = Box<dyn Fn(&X) -> f64>;
@@ -0,0 +1,93 @@
+use super::{PsOpt, PsoConfig, Swarm};
+use crate::parallel::MaybeSync;
+use crate::termination::{RunCheckpoint, RunState};
+use crate::{MaxIterations, Rng};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::ops::*;
+
+/// saved state of a particle swarm optimization between two moves
+///
+/// The checkpoint holds the config, the particles with their velocities and best
+/// known positions, the bounds, the iteration counter, the history and the state
+/// of the `Rng`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PsoCheckpoint<T> {
+    config: PsoConfig,
+    swarm: Swarm<T>,
+    run: RunCheckpoint,
+    rng_seed: u64,
+}
+
+impl<T, F, O> PsOpt<T, F>
+where
+    F: Fn(&[T]) -> O + MaybeSync,
+    O: PartialOrd + Into<f64>,
+    T: Copy
+        + Debug
+        + AddAssign
+        + Sub
+        + std::ops::Sub<Output = T>
+        + From<f32>
+        + Into<f32>
+        + MaybeSync,
+{
+    /// save the state of the swarm, to be serialized
+    pub fn checkpoint(&self) -> PsoCheckpoint<T> {
+        PsoCheckpoint {
+            config: self.config,
+            swarm: self.swarm.clone(),
+            run: self.run_state.checkpoint(),
+            rng_seed: self.rng.get_seed(),
+        }
+    }
+    /// continue the optimization where the checkpoint was saved
+    ///
+    /// Termination and observers start from the defaults of [`PsOpt::init`].
+    pub fn resume(checkpoint: PsoCheckpoint<T>, fitness: F) -> Self {
+        let config = checkpoint.config;
+        PsOpt {
+            swarm: checkpoint.swarm,
+            fitness,
+            config,
+            termination: Box::new(MaxIterations(config.iteration)),
+            observers: vec![],
+            run_state: RunState::resume(checkpoint.run),
+            rng: Rng::with_seed(checkpoint.rng_seed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::pso;
+    use super::*;
+    use crate::testing::{assert_resumed_run_matches, failing_sphere, random_particle, sphere};
+    use crate::NanPolicy;
+
+    #[test]
+    fn resumed_run_matches_uninterrupted_run() {
+        assert_resumed_run_matches(
+            || pso(9),
+            15,
+            PsOpt::checkpoint,
+            |checkpoint| PsOpt::resume(checkpoint, sphere as fn(&_) -> f32),
+        );
+    }
+
+    #[test]
+    fn resumed_run_keeps_worst_fitness() {
+        let config = PsoConfig::builder(20, 30)
+            .nan_policy(NanPolicy::Worst)
+            .build()
+            .unwrap();
+        let fitness = failing_sphere as fn(&_) -> f32;
+        let saved = assert_resumed_run_matches(
+            || PsOpt::init_with_rng(config, fitness, random_particle, Rng::with_seed(9)),
+            15,
+            PsOpt::checkpoint,
+            |checkpoint| PsOpt::resume(checkpoint, fitness),
+        );
+        assert!(saved.contains("\"inf\""));
+    }
+}
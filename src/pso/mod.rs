@@ -1,16 +1,79 @@
 //! Particle Swarm Optimization Framework
+use crate::error::MincostError;
+use crate::observer;
+use crate::parallel::{self, MaybeSync};
+use crate::termination::RunState;
+use crate::{
+    DynFitness, IterationStats, MaxIterations, NanPolicy, Objective, Observer, Optimizer, Problem,
+    Progress, Rng, RunHistory, Snapshot, StopReason, Termination,
+};
 use std::fmt::Debug;
 use std::iter::repeat_with;
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+mod checkpoint;
+
+#[cfg(feature = "serde")]
+pub use checkpoint::*;
 
 type Solution<T> = Vec<T>;
 
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Particle<T> {
     position: Vec<T>,
     velocity: Vec<T>,
     best_known_position: Vec<T>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite"))]
+    best_known_cost: f64,
 }
 
 impl<T> Particle<T> {
+    /// particle at `position` moving by `velocity`, its best known position is the start
+    pub fn new(position: Vec<T>, velocity: Vec<T>) -> Self
+    where
+        T: Clone,
+    {
+        Particle {
+            best_known_position: position.clone(),
+            best_known_cost: f64::NAN, // evaluated as the swarm is initialized
+            position,
+            velocity,
+        }
+    }
+    /// particle at a uniformly random position within `(lower, upper)` bound of each
+    /// dimension, with a uniformly random velocity within the width of each bound
+    pub fn random(bounds: &[(f32, f32)], rng: &Rng) -> Self
+    where
+        T: From<f32> + Clone,
+    {
+        let position = bounds
+            .iter()
+            .map(|(lower, upper)| T::from(lower + (upper - lower) * rng.f32()))
+            .collect();
+        let velocity = bounds
+            .iter()
+            .map(|(lower, upper)| T::from((upper - lower) * (2.0 * rng.f32() - 1.0)))
+            .collect();
+        Particle::new(position, velocity)
+    }
+    /// current position
+    pub fn position(&self) -> &[T] {
+        &self.position
+    }
+    /// current velocity
+    pub fn velocity(&self) -> &[T] {
+        &self.velocity
+    }
+    /// best position this particle has visited
+    pub fn best_known_position(&self) -> &[T] {
+        &self.best_known_position
+    }
+    /// fitness of the best position this particle has visited
+    pub fn best_known_cost(&self) -> f64 {
+        self.best_known_cost
+    }
     fn update_position(&mut self, lr: f32, dimension: usize)
     where
         T: std::ops::AddAssign + Into<f32> + From<f32> + Copy,
@@ -19,20 +82,98 @@ impl<T> Particle<T> {
             self.position[d] += T::from(lr * self.velocity[d].into());
         }
     }
+    // clamp the position within the `(lower, upper)` bound of each dimension, the
+    // particle stops in the dimensions where it hits a bound; returns whether it moved
+    fn confine(&mut self, bounds: &[(f32, f32)]) -> bool
+    where
+        T: Into<f32> + From<f32> + Copy,
+    {
+        let mut moved = false;
+        let dimensions = self.position.iter_mut().zip(self.velocity.iter_mut());
+        for ((x, v), (lower, upper)) in dimensions.zip(bounds) {
+            let clamped = (*x).into().max(*lower).min(*upper);
+            if clamped != (*x).into() {
+                *x = T::from(clamped);
+                *v = T::from(0.0);
+                moved = true;
+            }
+        }
+        moved
+    }
+    // move once, pulled toward the best known positions of the particle and the swarm,
+    // and kept within `bounds` if any
+    fn fly(
+        &mut self,
+        swarm_best: &[T],
+        bounds: Option<&[(f32, f32)]>,
+        config: &PsoConfig,
+        rng: &Rng,
+    ) where
+        T: std::ops::AddAssign + std::ops::Sub<Output = T> + Into<f32> + From<f32> + Copy,
+    {
+        for (d, best) in swarm_best.iter().enumerate() {
+            // update velocity in each dimension of particle
+            let r_p = rng.f32();
+            let r_g = rng.f32();
+            self.velocity[d] = T::from(
+                config.omega * self.velocity[d].into()
+                    + config.phi_p * r_p * (self.best_known_position[d] - self.position[d]).into()
+                    + config.phi_g * r_g * (*best - self.position[d]).into(),
+            );
+        }
+        self.update_position(config.learning_rate, swarm_best.len());
+        if let Some(bounds) = bounds {
+            self.confine(bounds);
+        }
+    }
+    // fly again from the `previous` particle without momentum, pulled toward the best
+    // known positions only
+    fn fly_again(
+        &mut self,
+        previous: Option<&Particle<T>>,
+        swarm_best: &[T],
+        bounds: Option<&[(f32, f32)]>,
+        config: &PsoConfig,
+        rng: &Rng,
+    ) where
+        T: std::ops::AddAssign + std::ops::Sub<Output = T> + Into<f32> + From<f32> + Copy,
+    {
+        if let Some(previous) = previous {
+            self.clone_from(previous);
+        }
+        self.velocity.iter_mut().for_each(|v| *v = T::from(0.0));
+        self.fly(swarm_best, bounds, config, rng);
+    }
 }
 
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Swarm<T> {
     population: Vec<Particle<T>>,
     best_known_position: Vec<T>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite"))]
+    best_known_cost: f64,
+    // fitness of each particle at its current position
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite::vec"))]
+    costs: Vec<f64>,
+    evaluations: usize,
+    // `(lower, upper)` bound of each dimension confining positions, unbounded if `None`
+    bounds: Option<Vec<(f32, f32)>>,
 }
 
 pub struct PsOpt<T, F> {
     swarm: Swarm<T>,
     fitness: F,
     config: PsoConfig,
+    termination: Box<dyn Termination>,
+    observers: Vec<Box<dyn Observer<T>>>,
+    run_state: RunState,
+    rng: Rng,
 }
 
+/// hyper parameter in particle swarm optimization
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PsoConfig {
     pop_size: usize,
     omega: f32, // w
@@ -40,68 +181,803 @@ pub struct PsoConfig {
     phi_p: f32,
     learning_rate: f32, // lr
     iteration: usize,
+    objective: Objective,
+    nan_policy: NanPolicy,
+}
+
+impl PsoConfig {
+    /// `pop_size` particles moving `iteration` times, with common defaults of
+    /// `omega = 0.7`, `phi_p = 1.5`, `phi_g = 1.5`, `learning_rate = 1.0`, minimized
+    /// fitness and [`NanPolicy::Error`], change them by [`PsoConfig::builder`]
+    pub fn new(pop_size: usize, iteration: usize) -> Self {
+        PsoConfig {
+            pop_size,
+            omega: 0.7,
+            phi_g: 1.5,
+            phi_p: 1.5,
+            learning_rate: 1.0,
+            iteration,
+            objective: Objective::Minimize,
+            nan_policy: NanPolicy::Error,
+        }
+    }
+    /// builder of `pop_size` particles moving `iteration` times, with the defaults of
+    /// [`PsoConfig::new`]
+    pub fn builder(pop_size: usize, iteration: usize) -> PsoConfigBuilder {
+        PsoConfigBuilder {
+            config: PsoConfig::new(pop_size, iteration),
+        }
+    }
+    /// error describing the first hyper parameter out of range
+    pub fn validate(&self) -> Result<(), MincostError> {
+        if self.pop_size == 0 {
+            Err(MincostError::InvalidConfig("pop_size is 0".to_string()))
+        } else if !self.omega.is_finite() {
+            Err(MincostError::InvalidConfig(format!(
+                "omega {} is not finite",
+                self.omega
+            )))
+        } else if !self.phi_g.is_finite() || self.phi_g < 0.0 {
+            Err(MincostError::InvalidConfig(format!(
+                "phi_g {} is not a finite non-negative number",
+                self.phi_g
+            )))
+        } else if !self.phi_p.is_finite() || self.phi_p < 0.0 {
+            Err(MincostError::InvalidConfig(format!(
+                "phi_p {} is not a finite non-negative number",
+                self.phi_p
+            )))
+        } else if !self.learning_rate.is_finite() || self.learning_rate <= 0.0 {
+            Err(MincostError::InvalidConfig(format!(
+                "learning_rate {} is not a finite positive number",
+                self.learning_rate
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// builder of a [`PsoConfig`], validated as it is built
+#[derive(Debug, Copy, Clone)]
+pub struct PsoConfigBuilder {
+    config: PsoConfig,
+}
+
+impl PsoConfigBuilder {
+    /// inertia weight of velocity
+    pub fn omega(mut self, omega: f32) -> Self {
+        self.config.omega = omega;
+        self
+    }
+    /// acceleration toward the swarm's best known position
+    pub fn phi_g(mut self, phi_g: f32) -> Self {
+        self.config.phi_g = phi_g;
+        self
+    }
+    /// acceleration toward the particle's best known position
+    pub fn phi_p(mut self, phi_p: f32) -> Self {
+        self.config.phi_p = phi_p;
+        self
+    }
+    /// scale of velocity applied to position
+    pub fn learning_rate(mut self, learning_rate: f32) -> Self {
+        self.config.learning_rate = learning_rate;
+        self
+    }
+    /// whether to minimize or maximize the fitness
+    pub fn objective(mut self, objective: Objective) -> Self {
+        self.config.objective = objective;
+        self
+    }
+    /// what becomes of a particle with NaN or infinite fitness
+    pub fn nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.config.nan_policy = nan_policy;
+        self
+    }
+    /// the config, or an error describing the first hyper parameter out of range
+    pub fn build(self) -> Result<PsoConfig, MincostError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
 }
 
 impl<T> Swarm<T> {
-    fn initial_random_pop<R: Fn() -> Particle<T>>(pop_size: usize, randness: R) -> Self {
-        Swarm {
-            population: repeat_with(|| randness()).take(pop_size).collect(),
-            best_known_position: randness().best_known_position,
+    // initial random population, the swarm knows the best position among particles
+    fn initial_random_pop<R, F, O>(
+        config: &PsoConfig,
+        randness: R,
+        fitness: &F,
+        rng: &Rng,
+    ) -> Result<Self, MincostError>
+    where
+        R: Fn(&Rng) -> Particle<T>,
+        F: Fn(&[T]) -> O + MaybeSync,
+        O: Into<f64>,
+        T: Clone + MaybeSync,
+    {
+        let mut population: Vec<Particle<T>> = repeat_with(|| randness(rng))
+            .take(config.pop_size)
+            .collect();
+        let mut costs = vec![f64::NAN; population.len()];
+        let evaluations = Self::evaluate(&mut population, &mut costs, config, fitness, |_, p| {
+            *p = randness(rng)
+        })?;
+        for (p, cost) in population.iter_mut().zip(costs.iter()) {
+            p.best_known_cost = *cost;
         }
+        let mut swarm = Swarm {
+            best_known_position: vec![],
+            best_known_cost: f64::NAN,
+            costs,
+            evaluations,
+            population,
+            bounds: None,
+        };
+        swarm.find_best(config.objective);
+        Ok(swarm)
     }
-    fn update_swarm<O: PartialOrd>(
+    // the swarm knows the best known position among particles
+    fn find_best(&mut self, objective: Objective)
+    where
+        T: Clone,
+    {
+        let mut best = &self.population[0];
+        for p in self.population.iter().skip(1) {
+            if objective.is_better(&p.best_known_cost, &best.best_known_cost) {
+                best = p;
+            }
+        }
+        self.best_known_position = best.best_known_position.clone();
+        self.best_known_cost = best.best_known_cost;
+    }
+    // clamp the particles of the initial swarm within `bounds`, then evaluate the moved
+    // ones as they start there, a rejected particle is drawn again uniformly within
+    // the bounds
+    fn confine_initial<F, O>(
         &mut self,
+        bounds: Vec<(f32, f32)>,
         config: &PsoConfig,
-        fitness: &dyn Fn(&Solution<T>) -> O,
-    ) where
-        T: std::ops::AddAssign + std::ops::Sub<Output = T> + Into<f32> + From<f32> + Copy,
+        fitness: &F,
+        rng: &Rng,
+    ) -> Result<(), MincostError>
+    where
+        F: Fn(&[T]) -> O + MaybeSync,
+        O: Into<f64>,
+        T: Into<f32> + From<f32> + Copy + MaybeSync,
     {
-        let dimension = self.best_known_position.len();
-        for p in self.population.iter_mut() {
-            for d in 0..dimension {
-                // update velocity in each dimension of particle
-                let r_p = fastrand::f32();
-                let r_g = fastrand::f32();
-                p.velocity[d] = T::from(
-                    config.omega * p.velocity[d].into()
-                        + config.phi_p * r_p * (p.best_known_position[d] - p.position[d]).into()
-                        + config.phi_g * r_g * (self.best_known_position[d] - p.position[d]).into(),
-                );
-            }
-            p.update_position(config.learning_rate, dimension);
-            if fitness(&p.position) < fitness(&p.best_known_position) {
-                p.best_known_position = p.position.clone();
-                if fitness(&p.best_known_position) < fitness(&self.best_known_position) {
-                    self.best_known_position = p.best_known_position.clone();
+        if bounds.len() != self.best_known_position.len() {
+            return Err(MincostError::InvalidConfig(format!(
+                "{} bounds for particles of {} dimensions",
+                bounds.len(),
+                self.best_known_position.len()
+            )));
+        }
+        let moved: Vec<usize> = (0..self.population.len())
+            .filter(|i| self.population[*i].confine(&bounds))
+            .collect();
+        let mut particles: Vec<Particle<T>> =
+            moved.iter().map(|i| self.population[*i].clone()).collect();
+        let mut costs = vec![f64::NAN; particles.len()];
+        self.evaluations += Self::evaluate(&mut particles, &mut costs, config, fitness, |_, p| {
+            *p = Particle::random(&bounds, rng)
+        })?;
+        for ((i, mut p), cost) in moved.into_iter().zip(particles).zip(costs) {
+            p.best_known_position = p.position.clone();
+            p.best_known_cost = cost;
+            self.population[i] = p;
+            self.costs[i] = cost;
+        }
+        self.find_best(config.objective);
+        self.bounds = Some(bounds);
+        Ok(())
+    }
+    // evaluate the position of every particle under the NaN policy of the config into
+    // `costs`, `resample` moves a rejected particle again; returns the number of evaluations
+    fn evaluate<F, O, S>(
+        population: &mut [Particle<T>],
+        costs: &mut [f64],
+        config: &PsoConfig,
+        fitness: &F,
+        mut resample: S,
+    ) -> Result<usize, MincostError>
+    where
+        F: Fn(&[T]) -> O + MaybeSync,
+        O: Into<f64>,
+        T: MaybeSync,
+        S: FnMut(usize, &mut Particle<T>),
+    {
+        let mut evaluations = 0;
+        let mut pending: Vec<usize> = (0..population.len()).collect();
+        let mut attempt = 0;
+        while !pending.is_empty() {
+            let positions: Vec<&[T]> = pending
+                .iter()
+                .map(|i| &population[*i].position[..])
+                .collect();
+            let evaluated =
+                parallel::evaluate(&positions, &|position: &[T]| fitness(position).into());
+            evaluations += evaluated.len();
+            let mut rejected = vec![];
+            for (i, cost) in pending.into_iter().zip(evaluated) {
+                match config.nan_policy.apply(cost, config.objective, attempt)? {
+                    Some(cost) => costs[i] = cost,
+                    None => {
+                        resample(i, &mut population[i]);
+                        rejected.push(i);
+                    }
                 }
             }
+            pending = rejected;
+            attempt += 1;
+        }
+        Ok(evaluations)
+    }
+    // mean euclidean distance from particles to the best known position of the swarm
+    fn diversity(&self) -> f64
+    where
+        T: Into<f32> + Copy,
+    {
+        let distance = |p: &Particle<T>| -> f64 {
+            p.position
+                .iter()
+                .zip(self.best_known_position.iter())
+                .map(|(x, g)| ((*x).into() as f64 - (*g).into() as f64).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        };
+        self.population.iter().map(distance).sum::<f64>() / self.population.len() as f64
+    }
+    // keep the position of particle `i` at `cost` if it is the best known one of the
+    // particle, and of the swarm; returns whether the swarm's best is improved
+    fn update_best(&mut self, i: usize, cost: f64, objective: Objective) -> bool
+    where
+        T: Clone,
+    {
+        let p = &mut self.population[i];
+        if !objective.is_better(&cost, &p.best_known_cost) {
+            return false;
+        }
+        p.best_known_position = p.position.clone();
+        p.best_known_cost = cost;
+        if !objective.is_better(&cost, &self.best_known_cost) {
+            return false;
         }
+        self.best_known_position = p.best_known_position.clone();
+        self.best_known_cost = cost;
+        true
+    }
+    // move every particle once, each costs one fitness evaluation unless resampled,
+    // returns whether the best known position of the swarm is improved; each particle
+    // is pulled toward the best known position of the swarm as updated by the particles
+    // moved before it
+    #[cfg(not(feature = "parallel"))]
+    fn update_swarm<F, O>(
+        &mut self,
+        config: &PsoConfig,
+        fitness: &F,
+        rng: &Rng,
+    ) -> Result<bool, MincostError>
+    where
+        F: Fn(&[T]) -> O + MaybeSync,
+        O: Into<f64>,
+        T: std::ops::AddAssign
+            + std::ops::Sub<Output = T>
+            + Into<f32>
+            + From<f32>
+            + Copy
+            + MaybeSync,
+    {
+        let resample = matches!(config.nan_policy, NanPolicy::Resample(_));
+        let mut improved = false;
+        for i in 0..self.population.len() {
+            // particle before the move, to move it again if rejected
+            let previous = resample.then(|| self.population[i].clone());
+            let swarm_best = &self.best_known_position;
+            let bounds = self.bounds.as_deref();
+            let p = &mut self.population[i];
+            p.fly(swarm_best, bounds, config, rng);
+            let mut cost = [f64::NAN];
+            self.evaluations += Self::evaluate(
+                std::slice::from_mut(p),
+                &mut cost,
+                config,
+                fitness,
+                |_, p| p.fly_again(previous.as_ref(), swarm_best, bounds, config, rng),
+            )?;
+            self.costs[i] = cost[0];
+            improved |= self.update_best(i, cost[0], config.objective);
+        }
+        Ok(improved)
+    }
+    // move every particle once, each costs one fitness evaluation unless resampled,
+    // returns whether the best known position of the swarm is improved; the whole swarm
+    // moves toward the best known position of the last move, then is evaluated at once
+    #[cfg(feature = "parallel")]
+    fn update_swarm<F, O>(
+        &mut self,
+        config: &PsoConfig,
+        fitness: &F,
+        rng: &Rng,
+    ) -> Result<bool, MincostError>
+    where
+        F: Fn(&[T]) -> O + MaybeSync,
+        O: Into<f64>,
+        T: std::ops::AddAssign
+            + std::ops::Sub<Output = T>
+            + Into<f32>
+            + From<f32>
+            + Copy
+            + MaybeSync,
+    {
+        // particles before the move, to move a rejected particle again
+        let previous =
+            matches!(config.nan_policy, NanPolicy::Resample(_)).then(|| self.population.clone());
+        let swarm_best = &self.best_known_position;
+        let bounds = self.bounds.as_deref();
+        for p in self.population.iter_mut() {
+            p.fly(swarm_best, bounds, config, rng);
+        }
+        // evaluate the moved swarm at once, then update best known positions
+        let mut costs = vec![f64::NAN; self.population.len()];
+        self.evaluations +=
+            Self::evaluate(&mut self.population, &mut costs, config, fitness, |i, p| {
+                let previous = previous.as_ref().map(|previous| &previous[i]);
+                p.fly_again(previous, swarm_best, bounds, config, rng)
+            })?;
+        let mut improved = false;
+        for (i, cost) in costs.iter().enumerate() {
+            improved |= self.update_best(i, *cost, config.objective);
+        }
+        self.costs = costs;
+        Ok(improved)
     }
 }
 use std::ops::*;
 impl<T, F, O> PsOpt<T, F>
 where
-    F: Fn(&Solution<T>) -> O,
-    O: PartialOrd,
-    T: Copy + Debug + AddAssign + Sub + std::ops::Sub<Output = T> + From<f32> + Into<f32>,
+    F: Fn(&[T]) -> O + MaybeSync,
+    O: PartialOrd + Into<f64>,
+    T: Copy
+        + Debug
+        + AddAssign
+        + Sub
+        + std::ops::Sub<Output = T>
+        + From<f32>
+        + Into<f32>
+        + MaybeSync,
 {
-    pub fn init<R: Fn() -> Particle<T>>(config: PsoConfig, fitness: F, randness: R) -> Self
-    where
-        F: Fn(&Particle<T>) -> O,
-        O: Into<f64>,
-    {
-        let swarm = Swarm::initial_random_pop(config.pop_size, randness);
-        PsOpt {
+    /// initial swarm of random particles, panics on an invalid config
+    pub fn init<R: Fn(&Rng) -> Particle<T>>(config: PsoConfig, fitness: F, randness: R) -> Self {
+        Self::init_with_rng(config, fitness, randness, Rng::new())
+    }
+    /// initial swarm drawing all randomness from `rng`, a seeded `Rng` makes the run reproducible,
+    /// panics on an invalid config
+    pub fn init_with_rng<R: Fn(&Rng) -> Particle<T>>(
+        config: PsoConfig,
+        fitness: F,
+        randness: R,
+        rng: Rng,
+    ) -> Self {
+        Self::try_init_with_rng(config, fitness, randness, rng).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// initial swarm, or an error for an invalid config or initial fitness
+    pub fn try_init<R: Fn(&Rng) -> Particle<T>>(
+        config: PsoConfig,
+        fitness: F,
+        randness: R,
+    ) -> Result<Self, MincostError> {
+        Self::try_init_with_rng(config, fitness, randness, Rng::new())
+    }
+    /// initial swarm drawing all randomness from `rng`, or an error for an invalid config
+    /// or initial fitness
+    pub fn try_init_with_rng<R: Fn(&Rng) -> Particle<T>>(
+        config: PsoConfig,
+        fitness: F,
+        randness: R,
+        rng: Rng,
+    ) -> Result<Self, MincostError> {
+        config.validate()?;
+        let swarm = Swarm::initial_random_pop(&config, randness, &fitness, &rng)?;
+        let mut pso = PsOpt {
             swarm,
             fitness,
             config,
+            termination: Box::new(MaxIterations(config.iteration)),
+            observers: vec![],
+            run_state: RunState::new(),
+            rng,
+        };
+        pso.log_swarm();
+        Ok(pso)
+    }
+    /// replace the stopping condition, [`MaxIterations`] of `iteration` by default
+    pub fn with_termination<C: Termination + 'static>(mut self, termination: C) -> Self {
+        self.termination = Box::new(termination);
+        self
+    }
+    /// keep particles within the `(lower, upper)` bound of each dimension, a particle
+    /// hitting a bound stops there in that dimension; unbounded by default
+    ///
+    /// Set before the first move, the initial particles outside the bounds are clamped
+    /// within them and evaluated again. Panics on a number of bounds other than the
+    /// dimension of particles, bounds set after a move, or an invalid fitness.
+    pub fn with_bounds(self, bounds: Vec<(f32, f32)>) -> Self {
+        self.try_with_bounds(bounds)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// keep particles within the `(lower, upper)` bound of each dimension as
+    /// [`PsOpt::with_bounds`], or an error for a number of bounds other than the
+    /// dimension of particles, bounds set after a move, or an invalid fitness
+    pub fn try_with_bounds(mut self, bounds: Vec<(f32, f32)>) -> Result<Self, MincostError> {
+        if self.run_state.iteration() > 0 {
+            return Err(MincostError::InvalidConfig(
+                "bounds are set after the swarm moved".to_string(),
+            ));
+        }
+        self.swarm
+            .confine_initial(bounds, &self.config, &self.fitness, &self.rng)?;
+        // statistics of the initial swarm as confined
+        self.run_state = RunState::new();
+        self.log_swarm();
+        Ok(self)
+    }
+    /// register an observer invoked after every move of the swarm
+    pub fn with_observer<B: Observer<T> + 'static>(mut self, observer: B) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+    /// fitness evaluations spent so far
+    pub fn evaluations(&self) -> usize {
+        self.swarm.evaluations
+    }
+    /// fitness of the best position the swarm has visited
+    pub fn best_cost(&self) -> f64 {
+        self.swarm.best_known_cost
+    }
+    /// progress of the run so far, the diversity is the mean euclidean distance from
+    /// particles to the best known position of the swarm
+    pub fn progress(&self) -> Progress {
+        self.run_state.progress(
+            self.swarm.evaluations,
+            self.swarm.best_known_cost,
+            Some(self.swarm.diversity()),
+            self.config.objective,
+        )
+    }
+    /// criterion which stopped the last run, `None` if it has not stopped yet
+    pub fn stop_reason(&self) -> Option<&StopReason> {
+        self.run_state.stop_reason()
+    }
+    /// statistics of every move so far, over the fitness of particles at their positions
+    pub fn history(&self) -> &RunHistory {
+        self.run_state.history()
+    }
+    // record statistics of the swarm at current positions
+    fn log_swarm(&mut self) {
+        let stats = IterationStats {
+            diversity: Some(self.swarm.diversity()),
+            ..IterationStats::new(
+                self.run_state.iteration(),
+                self.swarm.evaluations,
+                &self.swarm.costs,
+                self.swarm.best_known_cost,
+                self.config.objective,
+            )
+        };
+        self.run_state.log(stats);
+    }
+    // move the swarm once
+    fn move_swarm(&mut self) -> Result<(), MincostError> {
+        let improved = self
+            .swarm
+            .update_swarm(&self.config, &self.fitness, &self.rng)?;
+        self.run_state.record(improved);
+        self.log_swarm();
+        self.notify();
+        Ok(())
+    }
+    // show the swarm to observers, the population is made of particle positions
+    fn notify(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let progress = self.progress();
+        let snapshot = Snapshot {
+            progress,
+            stats: *self.run_state.history().last().unwrap(),
+            best: &self.swarm.best_known_position,
+            population: self
+                .swarm
+                .population
+                .iter()
+                .map(|p| &p.position[..])
+                .collect(),
+        };
+        if observer::notify(&mut self.observers, &snapshot) {
+            self.run_state.request_stop();
         }
     }
     pub fn optimize(&mut self) -> Solution<T> {
-        // reach iteration number as termination criterion
-        for _ in 0..self.config.iteration {
-            self.swarm.update_swarm(&self.config, &self.fitness);
+        self.try_optimize().unwrap_or_else(|e| panic!("{}", e))
+    }
+    // error for an invalid config or genome, before moving
+    fn check(&self) -> Result<(), MincostError> {
+        self.config.validate()?;
+        if self.swarm.best_known_position.is_empty() {
+            return Err(MincostError::EmptyGenome);
+        }
+        Ok(())
+    }
+    /// move the swarm until the termination fires, reporting an invalid fitness,
+    /// genome or config as error instead of panicking
+    pub fn try_optimize(&mut self) -> Result<Solution<T>, MincostError> {
+        self.check()?;
+        loop {
+            let progress = self.progress();
+            if self
+                .run_state
+                .should_stop(self.termination.as_ref(), &progress)
+            {
+                break;
+            }
+            self.move_swarm()?;
+        }
+        Ok(self.swarm.best_known_position.clone())
+    }
+}
+
+impl<T> PsOpt<T, DynFitness<[T]>>
+where
+    T: Copy
+        + Debug
+        + AddAssign
+        + Sub
+        + std::ops::Sub<Output = T>
+        + From<f32>
+        + Into<f32>
+        + MaybeSync
+        + 'static,
+{
+    /// initial swarm solving `problem`, particles start with a random velocity within
+    /// the width of each bound and are kept within the bounds, or start at rest if the
+    /// problem is unbounded
+    pub fn from_problem<P>(config: PsoConfig, problem: P) -> Self
+    where
+        P: Problem<T> + 'static,
+        Arc<P>: MaybeSync,
+    {
+        Self::from_problem_with_rng(config, problem, Rng::new())
+    }
+    /// initial swarm solving `problem`, drawing all randomness from `rng`
+    pub fn from_problem_with_rng<P>(config: PsoConfig, problem: P, rng: Rng) -> Self
+    where
+        P: Problem<T> + 'static,
+        Arc<P>: MaybeSync,
+    {
+        let problem = Arc::new(problem);
+        let config = PsoConfig {
+            objective: problem.objective(),
+            ..config
+        };
+        let bounds = problem.bounds();
+        let randness = |rng: &Rng| {
+            let position = problem.random(rng);
+            let velocity = match &bounds {
+                Some(bounds) => bounds
+                    .iter()
+                    .map(|(lower, upper)| {
+                        T::from(((upper - lower) * (2.0 * rng.f64() - 1.0)) as f32)
+                    })
+                    .collect(),
+                None => vec![T::from(0.0); position.len()],
+            };
+            Particle::new(position, velocity)
+        };
+        let fitness: DynFitness<[T]> = {
+            let problem = problem.clone();
+            Box::new(move |position: &[T]| problem.cost(position))
+        };
+        let pso = Self::init_with_rng(config, fitness, randness, rng);
+        match bounds {
+            Some(bounds) => {
+                pso.with_bounds(bounds.iter().map(|(l, u)| (*l as f32, *u as f32)).collect())
+            }
+            None => pso,
+        }
+    }
+}
+
+impl<T, F, O> Optimizer<T> for PsOpt<T, F>
+where
+    F: Fn(&[T]) -> O + MaybeSync,
+    O: PartialOrd + Into<f64>,
+    T: Copy
+        + Debug
+        + AddAssign
+        + Sub
+        + std::ops::Sub<Output = T>
+        + From<f32>
+        + Into<f32>
+        + MaybeSync,
+{
+    /// move every particle once
+    fn try_step(&mut self) -> Result<bool, MincostError> {
+        self.check()?;
+        self.move_swarm()?;
+        Ok(!self.run_state.stop_requested())
+    }
+    fn run(&mut self) -> Vec<T> {
+        self.optimize()
+    }
+    fn best(&self) -> &[T] {
+        &self.swarm.best_known_position
+    }
+    fn best_cost(&self) -> f64 {
+        self.swarm.best_known_cost
+    }
+    fn stop_reason(&self) -> Option<&StopReason> {
+        self.run_state.stop_reason()
+    }
+    fn history(&self) -> &RunHistory {
+        self.run_state.history()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_same_run, random_particle, sphere};
+    use crate::Control;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    pub(super) fn pso(seed: u64) -> PsOpt<f32, fn(&[f32]) -> f32> {
+        let config = PsoConfig::new(20, 30);
+        PsOpt::init_with_rng(
+            config,
+            sphere as fn(&_) -> f32,
+            random_particle,
+            Rng::with_seed(seed),
+        )
+    }
+
+    // best position and fitness of the asynchronous update, as the swarm moved before
+    // the evaluation of a whole swarm at once, drawing randomness in the same order
+    #[cfg(not(feature = "parallel"))]
+    fn asynchronous(config: &PsoConfig, bounds: &[(f32, f32)], rng: &Rng) -> (Vec<f32>, f64) {
+        let mut population: Vec<Particle<f32>> = (0..config.pop_size)
+            .map(|_| Particle::random(bounds, rng))
+            .collect();
+        let mut best_position = population[0].position.clone();
+        let mut best_cost = f64::NAN;
+        for p in population.iter_mut() {
+            p.best_known_cost = sphere(&p.position) as f64;
+            if best_cost.is_nan() || p.best_known_cost < best_cost {
+                best_position = p.position.clone();
+                best_cost = p.best_known_cost;
+            }
         }
-        self.swarm.best_known_position.clone()
+        for _ in 0..config.iteration {
+            for p in population.iter_mut() {
+                for (d, best) in best_position.iter().enumerate() {
+                    let r_p = rng.f32();
+                    let r_g = rng.f32();
+                    p.velocity[d] = config.omega * p.velocity[d]
+                        + config.phi_p * r_p * (p.best_known_position[d] - p.position[d])
+                        + config.phi_g * r_g * (best - p.position[d]);
+                }
+                for (x, v) in p.position.iter_mut().zip(p.velocity.iter()) {
+                    *x += config.learning_rate * v;
+                }
+                let cost = sphere(&p.position) as f64;
+                if cost < p.best_known_cost {
+                    p.best_known_position = p.position.clone();
+                    p.best_known_cost = cost;
+                    if cost < best_cost {
+                        best_position = p.position.clone();
+                        best_cost = cost;
+                    }
+                }
+            }
+        }
+        (best_position, best_cost)
+    }
+
+    #[test]
+    #[cfg(not(feature = "parallel"))]
+    fn sequential_run_updates_swarm_best_after_each_particle() {
+        let bounds = [(-5.0, 5.0); 3];
+        let config = PsoConfig::new(20, 30);
+        let expected = asynchronous(&config, &bounds, &Rng::with_seed(7));
+        let mut pso = PsOpt::init_with_rng(
+            config,
+            sphere,
+            |rng: &Rng| Particle::random(&bounds, rng),
+            Rng::with_seed(7),
+        );
+        let best = pso.optimize();
+        assert_eq!((best, pso.best_cost()), expected);
+    }
+
+    #[test]
+    fn same_seed_gives_identical_run() {
+        assert_same_run(|| pso(7));
+    }
+
+    #[test]
+    fn each_particle_costs_one_evaluation_per_move() {
+        let calls = AtomicUsize::new(0);
+        let counted = |position: &[f32]| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            sphere(position)
+        };
+        let config = PsoConfig::new(20, 30);
+        let mut pso = PsOpt::init_with_rng(config, counted, random_particle, Rng::with_seed(7));
+        assert_eq!(pso.evaluations(), 20);
+        for moves in 1..=5 {
+            pso.step();
+            assert_eq!(pso.evaluations(), 20 + 20 * moves);
+        }
+        pso.optimize();
+        assert_eq!(pso.evaluations(), 20 + 20 * 30);
+        let evaluations = pso.evaluations();
+        assert_eq!(
+            calls.load(std::sync::atomic::Ordering::Relaxed),
+            evaluations
+        );
+    }
+
+    #[test]
+    fn bounded_particles_stay_within_bounds() {
+        let outward = |position: &[f32]| -position.iter().sum::<f32>();
+        let config = PsoConfig::new(10, 30);
+        let mut pso = PsOpt::init_with_rng(config, outward, random_particle, Rng::with_seed(7))
+            .with_bounds(vec![(-5.0, 5.0); 3])
+            .with_observer(|snapshot: &Snapshot<f32>| {
+                let within = |x: &f32| (-5.0..=5.0).contains(x);
+                assert!(snapshot.population.iter().all(|p| p.iter().all(within)));
+                Control::Continue
+            });
+        assert_eq!(pso.optimize(), vec![5.0; 3]);
+    }
+
+    #[test]
+    fn initial_particles_are_confined_before_evaluation() {
+        let sum = |position: &[f32]| position.iter().sum::<f32>();
+        let config = PsoConfig::new(10, 30);
+        let mut pso = PsOpt::init_with_rng(config, sum, random_particle, Rng::with_seed(7))
+            .with_bounds(vec![(0.0, 1.0); 3]);
+        let within = |x: &f32| (0.0..=1.0).contains(x);
+        for p in pso.swarm.population.iter() {
+            assert!(p.position().iter().all(within));
+            assert_eq!(p.best_known_cost(), sum(p.position()) as f64);
+        }
+        assert!(pso.swarm.best_known_position.iter().all(within));
+        assert_eq!(pso.history().records().len(), 1);
+        assert_eq!(pso.history().records()[0].evaluations, pso.evaluations());
+        assert_eq!(pso.optimize(), vec![0.0; 3]);
+    }
+
+    #[test]
+    fn out_of_range_config_is_rejected() {
+        let invalid = |builder: PsoConfigBuilder| {
+            matches!(builder.build(), Err(MincostError::InvalidConfig(_)))
+        };
+        assert!(invalid(PsoConfig::builder(0, 5)));
+        assert!(invalid(PsoConfig::builder(10, 5).omega(f32::INFINITY)));
+        assert!(invalid(PsoConfig::builder(10, 5).phi_g(-1.0)));
+        assert!(invalid(PsoConfig::builder(10, 5).phi_p(f32::NAN)));
+        assert!(invalid(PsoConfig::builder(10, 5).learning_rate(0.0)));
+        assert!(PsoConfig::new(10, 5).validate().is_ok());
+    }
+
+    #[test]
+    fn wrong_bound_count_is_reported_as_error() {
+        let result = pso(7).try_with_bounds(vec![(0.0, 1.0); 2]);
+        assert!(matches!(result, Err(MincostError::InvalidConfig(_))));
+        let mut moved = pso(7);
+        moved.step();
+        let result = moved.try_with_bounds(vec![(0.0, 1.0); 3]);
+        assert!(matches!(result, Err(MincostError::InvalidConfig(_))));
     }
 }
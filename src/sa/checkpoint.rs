@@ -0,0 +1,94 @@
+use super::{AnnealState, Annealer, AnnealerConfig, GeometricCooling, Solution};
+use crate::termination::{RunCheckpoint, RunState};
+use crate::{MaxIterations, Rng};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// saved state of an annealer between two temperature levels
+///
+/// The checkpoint holds the config, the current and best solutions, the
+/// temperature, the level counter, the history and the state of the `Rng`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnealerCheckpoint<T> {
+    config: AnnealerConfig,
+    state: AnnealState<T>,
+    level: usize,
+    evaluations: usize,
+    run: RunCheckpoint,
+    rng_seed: u64,
+}
+
+impl<T, F, O> Annealer<T, F>
+where
+    F: Fn(&Solution<T>) -> O,
+    O: PartialOrd + Into<f64>,
+    T: Copy + Debug,
+{
+    /// save the state of the annealer, to be serialized
+    pub fn checkpoint(&self) -> AnnealerCheckpoint<T> {
+        AnnealerCheckpoint {
+            config: self.config,
+            state: self.state.clone(),
+            level: self.level,
+            evaluations: self.evaluations,
+            run: self.run_state.checkpoint(),
+            rng_seed: self.rng.get_seed(),
+        }
+    }
+    /// continue the annealing where the checkpoint was saved
+    ///
+    /// Cooling schedule, neighbor, termination and observers start from the defaults
+    /// of [`Annealer::init`].
+    pub fn resume(checkpoint: AnnealerCheckpoint<T>, fitness: F) -> Self {
+        let config = checkpoint.config;
+        Annealer {
+            config,
+            state: checkpoint.state,
+            fitness,
+            cooling: Box::new(GeometricCooling {
+                alpha: config.alpha,
+            }),
+            neighbor: None,
+            level: checkpoint.level,
+            evaluations: checkpoint.evaluations,
+            termination: Box::new(MaxIterations(config.iteration)),
+            observers: vec![],
+            run_state: RunState::resume(checkpoint.run),
+            rng: Rng::with_seed(checkpoint.rng_seed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::annealer;
+    use super::*;
+    use crate::testing::{assert_resumed_run_matches, failing_tour, random_tour, tour};
+    use crate::NanPolicy;
+
+    #[test]
+    fn resumed_run_matches_uninterrupted_run() {
+        assert_resumed_run_matches(
+            || annealer(5),
+            17,
+            Annealer::checkpoint,
+            |checkpoint| Annealer::resume(checkpoint, tour as fn(&_) -> f64),
+        );
+    }
+
+    #[test]
+    fn resumed_run_keeps_worst_fitness() {
+        let config = AnnealerConfig::builder(10.0, 40)
+            .nan_policy(NanPolicy::Worst)
+            .build()
+            .unwrap();
+        let fitness = failing_tour as fn(&_) -> f64;
+        let saved = assert_resumed_run_matches(
+            || Annealer::init_with_rng(config, fitness, random_tour, Rng::with_seed(5)),
+            17,
+            Annealer::checkpoint,
+            |checkpoint| Annealer::resume(checkpoint, fitness),
+        );
+        assert!(saved.contains("\"NaN\""));
+    }
+}
@@ -0,0 +1,88 @@
+//! Cooling schedules
+//!
+//! A [`CoolingSchedule`] gives the temperature of the next level once the moves of
+//! a level are done. The schedules differ in how fast they cool early and late in
+//! the run, and [`ReheatCooling`] raises the temperature again periodically to
+//! escape local optima.
+
+/// strategy to lower the temperature between levels
+pub trait CoolingSchedule {
+    /// temperature of the next level, `level` counts the levels cooled so far
+    fn cool(&mut self, temperature_zero: f32, temperature: f32, level: usize) -> f32;
+}
+
+/// Geometric cooling, the default schedule: `T' = alpha * T`.
+#[derive(Debug, Copy, Clone)]
+pub struct GeometricCooling {
+    /// cooling factor, in 0 to 1
+    pub alpha: f32,
+}
+
+/// Linear cooling: `T' = T - step`, never below zero.
+#[derive(Debug, Copy, Clone)]
+pub struct LinearCooling {
+    /// temperature drop per level
+    pub step: f32,
+}
+
+/// Logarithmic cooling: `T = T0 / (1 + c * ln(1 + level))`.
+#[derive(Debug, Copy, Clone)]
+pub struct LogarithmicCooling {
+    /// cooling coefficient, greater than 0
+    pub c: f32,
+}
+
+/// Lundy-Mees cooling: `T' = T / (1 + beta * T)`.
+#[derive(Debug, Copy, Clone)]
+pub struct LundyMeesCooling {
+    /// cooling coefficient, greater than 0
+    pub beta: f32,
+}
+
+/// Exponential cooling with periodic reheat.
+///
+/// The temperature cools as `T' = alpha * T`, and every `period` levels it
+/// is raised back to `reheat * T0`.
+#[derive(Debug, Copy, Clone)]
+pub struct ReheatCooling {
+    /// cooling factor, in 0 to 1
+    pub alpha: f32,
+    /// levels between two reheats
+    pub period: usize,
+    /// reheated temperature, as a proportion of `T0`
+    pub reheat: f32,
+}
+
+impl CoolingSchedule for GeometricCooling {
+    fn cool(&mut self, _temperature_zero: f32, temperature: f32, _level: usize) -> f32 {
+        temperature * self.alpha
+    }
+}
+
+impl CoolingSchedule for LinearCooling {
+    fn cool(&mut self, _temperature_zero: f32, temperature: f32, _level: usize) -> f32 {
+        (temperature - self.step).max(0.0)
+    }
+}
+
+impl CoolingSchedule for LogarithmicCooling {
+    fn cool(&mut self, temperature_zero: f32, _temperature: f32, level: usize) -> f32 {
+        temperature_zero / (1.0 + self.c * (1.0 + level as f32).ln())
+    }
+}
+
+impl CoolingSchedule for LundyMeesCooling {
+    fn cool(&mut self, _temperature_zero: f32, temperature: f32, _level: usize) -> f32 {
+        temperature / (1.0 + self.beta * temperature)
+    }
+}
+
+impl CoolingSchedule for ReheatCooling {
+    fn cool(&mut self, temperature_zero: f32, temperature: f32, level: usize) -> f32 {
+        if self.period > 0 && level.is_multiple_of(self.period) {
+            self.reheat * temperature_zero
+        } else {
+            temperature * self.alpha
+        }
+    }
+}
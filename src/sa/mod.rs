@@ -1,72 +1,230 @@
 //! Simulated Annealing Framework
 #![allow(dead_code)]
+use crate::error::MincostError;
+use crate::observer;
+use crate::parallel::MaybeSync;
+use crate::termination::RunState;
+use crate::{
+    DynFitness, IterationStats, MaxIterations, NanPolicy, Objective, Observer, Optimizer, Problem,
+    Progress, Rng, RunHistory, Snapshot, StopReason, Termination,
+};
 use std::fmt::Debug;
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+mod checkpoint;
+mod cooling;
+
+#[cfg(feature = "serde")]
+pub use checkpoint::*;
+pub use cooling::*;
 
 // encoded solution
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Solution<T> {
     pub bits: Vec<T>,
 }
 
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AnnealState<T> {
     solution: Solution<T>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite"))]
+    cost: f64,
+    best_solution: Solution<T>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::non_finite"))]
+    best_cost: f64,
     temperature: f32,
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AnnealerConfig {
     pub alpha: f32,
     pub temperature_zero: f32,
     pub temperature_end: f32,
     pub iteration: usize,
+    /// Metropolis moves tried at each temperature level
+    pub moves_per_temperature: usize,
+    pub objective: Objective,
+    /// what becomes of a solution with NaN or infinite fitness
+    pub nan_policy: NanPolicy,
+}
+
+/// builder of an [`AnnealerConfig`], validated as it is built
+#[derive(Debug, Copy, Clone)]
+pub struct AnnealerConfigBuilder {
+    config: AnnealerConfig,
+}
+
+impl AnnealerConfig {
+    /// builder annealing from `temperature_zero` over `iteration` levels, with defaults of
+    /// `alpha = 0.9`, `temperature_end = 0.01`, `moves_per_temperature = 1`, minimized
+    /// fitness and [`NanPolicy::Error`]
+    pub fn builder(temperature_zero: f32, iteration: usize) -> AnnealerConfigBuilder {
+        AnnealerConfigBuilder {
+            config: AnnealerConfig {
+                alpha: 0.9,
+                temperature_zero,
+                temperature_end: 0.01,
+                iteration,
+                moves_per_temperature: 1,
+                objective: Objective::Minimize,
+                nan_policy: NanPolicy::Error,
+            },
+        }
+    }
+    /// error describing the first hyper parameter out of range
+    pub fn validate(&self) -> Result<(), MincostError> {
+        if self.alpha.is_nan() || self.alpha <= 0.0 || self.alpha >= 1.0 {
+            Err(MincostError::InvalidConfig(format!(
+                "alpha {} is not strictly within 0 to 1",
+                self.alpha
+            )))
+        } else if !self.temperature_zero.is_finite() || self.temperature_zero <= 0.0 {
+            Err(MincostError::InvalidConfig(format!(
+                "temperature_zero {} is not positive",
+                self.temperature_zero
+            )))
+        } else if !self.temperature_end.is_finite() || self.temperature_end <= 0.0 {
+            Err(MincostError::InvalidConfig(format!(
+                "temperature_end {} is not positive",
+                self.temperature_end
+            )))
+        } else if self.temperature_end > self.temperature_zero {
+            Err(MincostError::InvalidConfig(format!(
+                "temperature_end {} exceeds temperature_zero {}",
+                self.temperature_end, self.temperature_zero
+            )))
+        } else if self.moves_per_temperature == 0 {
+            Err(MincostError::InvalidConfig(
+                "moves_per_temperature is 0".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
 }
 
+impl AnnealerConfigBuilder {
+    /// cooling factor of the default [`GeometricCooling`], strictly in 0 to 1
+    pub fn alpha(mut self, alpha: f32) -> Self {
+        self.config.alpha = alpha;
+        self
+    }
+    /// temperature below which the annealing stops, positive and at most
+    /// `temperature_zero`
+    pub fn temperature_end(mut self, temperature_end: f32) -> Self {
+        self.config.temperature_end = temperature_end;
+        self
+    }
+    /// Metropolis moves tried at each temperature level
+    pub fn moves_per_temperature(mut self, moves_per_temperature: usize) -> Self {
+        self.config.moves_per_temperature = moves_per_temperature;
+        self
+    }
+    /// whether to minimize or maximize the fitness
+    pub fn objective(mut self, objective: Objective) -> Self {
+        self.config.objective = objective;
+        self
+    }
+    /// what becomes of a solution with NaN or infinite fitness
+    pub fn nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.config.nan_policy = nan_policy;
+        self
+    }
+    /// the config, or an error describing the first hyper parameter out of range
+    pub fn build(self) -> Result<AnnealerConfig, MincostError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+// user-defined neighbor of a solution
+type NeighborFn<T> = Box<dyn Fn(&Solution<T>, &Rng) -> Solution<T>>;
+
 pub struct Annealer<T, F> {
     config: AnnealerConfig,
     state: AnnealState<T>,
     fitness: F,
+    cooling: Box<dyn CoolingSchedule>,
+    // custom neighbor, swapping two random bits if `None`
+    neighbor: Option<NeighborFn<T>>,
+    // temperature levels cooled so far
+    level: usize,
+    evaluations: usize,
+    termination: Box<dyn Termination>,
+    observers: Vec<Box<dyn Observer<T>>>,
+    run_state: RunState,
+    rng: Rng,
 }
 
 impl<T> Solution<T>
 where
     T: Copy,
 {
-    fn neighbor(&self) -> Self {
+    fn neighbor(&self, rng: &Rng) -> Self {
         let mut new_solution = self.clone();
-        let idx1: usize = fastrand::usize(..new_solution.bits.len());
-        let idx2: usize = fastrand::usize(..new_solution.bits.len());
-        let tmp = &mut new_solution.bits[idx1].clone();
-        new_solution.bits[idx1] = new_solution.bits[idx2].clone();
-        new_solution.bits[idx2] = *tmp;
+        let idx1: usize = rng.usize(..new_solution.bits.len());
+        let idx2: usize = rng.usize(..new_solution.bits.len());
+        new_solution.bits.swap(idx1, idx2);
         new_solution
     }
 }
-use std::ops::*;
 impl<T> AnnealState<T>
 where
     T: Copy,
 {
-    fn initial_random_state<R: Fn() -> Solution<T>>(randness: R, temp0: f32) -> Self {
-        AnnealState {
-            solution: randness(),
-            temperature: temp0,
-        }
+    // random solution kept by the NaN policy, along with the number of evaluations
+    fn initial_random_state<R: Fn(&Rng) -> Solution<T>, O: Into<f64>>(
+        randness: R,
+        fitness: &dyn Fn(&Solution<T>) -> O,
+        config: &AnnealerConfig,
+        rng: &Rng,
+    ) -> Result<(Self, usize), MincostError> {
+        let (solution, cost, evaluations) = config.nan_policy.draw(
+            config.objective,
+            || randness(rng),
+            |solution| fitness(solution).into(),
+        )?;
+        let state = AnnealState {
+            best_solution: solution.clone(),
+            best_cost: cost,
+            solution,
+            cost,
+            temperature: config.temperature_zero,
+        };
+        Ok((state, evaluations))
     }
-    fn update_temperature(&mut self, alpha: f32) {
-        self.temperature *= alpha;
+    fn update_temperature(
+        &mut self,
+        cooling: &mut dyn CoolingSchedule,
+        temperature_zero: f32,
+        level: usize,
+    ) {
+        self.temperature = cooling.cool(temperature_zero, self.temperature, level);
     }
-    // possibility to acceptance neighbor solution
-    fn acceptance<O: PartialOrd + Into<f64> + Sub<Output = O>>(
+    // possibility to acceptance neighbor solution of the evaluated cost, a neighbor of
+    // the worst possible cost is never accepted
+    fn acceptance(
         &mut self,
-        fitness: &dyn Fn(&Solution<T>) -> O,
+        neighbor: Solution<T>,
+        neighbor_cost: f64,
+        objective: Objective,
+        rng: &Rng,
     ) {
-        let neighbor = self.solution.neighbor();
-        let delta: f64 = (fitness(&neighbor) - fitness(&self.solution)).into();
-        if delta < 0.0 {
+        // positive delta means neighbor is worse than current solution
+        let delta = match objective {
+            Objective::Minimize => neighbor_cost - self.cost,
+            Objective::Maximize => self.cost - neighbor_cost,
+        };
+        if delta < 0.0 || rng.f64() < (-delta.abs() / self.temperature as f64).exp() {
             self.solution = neighbor;
-        } else {
-            if fastrand::f64() < (-delta.abs() / self.temperature as f64).exp() {
-                self.solution = neighbor;
+            self.cost = neighbor_cost;
+            if objective.is_better(&self.cost, &self.best_cost) {
+                self.best_solution = self.solution.clone();
+                self.best_cost = self.cost;
             }
         }
     }
@@ -75,25 +233,370 @@ where
 impl<T, F, O> Annealer<T, F>
 where
     F: Fn(&Solution<T>) -> O,
-    O: PartialOrd + Into<f64> + Sub + Sub<Output = O>,
+    O: PartialOrd + Into<f64>,
     T: Copy + Debug,
 {
-    pub fn init<R: Fn() -> Solution<T>>(config: AnnealerConfig, fitness: F, randness: R) -> Self {
-        let state = AnnealState::initial_random_state(randness, config.temperature_zero);
-        Annealer {
+    /// initial annealer from a random solution, panics on an invalid config
+    pub fn init<R: Fn(&Rng) -> Solution<T>>(
+        config: AnnealerConfig,
+        fitness: F,
+        randness: R,
+    ) -> Self {
+        Self::init_with_rng(config, fitness, randness, Rng::new())
+    }
+    /// initial annealer drawing all randomness from `rng`, a seeded `Rng` makes the run reproducible,
+    /// panics on an invalid config
+    pub fn init_with_rng<R: Fn(&Rng) -> Solution<T>>(
+        config: AnnealerConfig,
+        fitness: F,
+        randness: R,
+        rng: Rng,
+    ) -> Self {
+        Self::try_init_with_rng(config, fitness, randness, rng).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// initial annealer, or an error for an invalid config or initial fitness
+    pub fn try_init<R: Fn(&Rng) -> Solution<T>>(
+        config: AnnealerConfig,
+        fitness: F,
+        randness: R,
+    ) -> Result<Self, MincostError> {
+        Self::try_init_with_rng(config, fitness, randness, Rng::new())
+    }
+    /// initial annealer drawing all randomness from `rng`, or an error for an invalid
+    /// config or initial fitness
+    pub fn try_init_with_rng<R: Fn(&Rng) -> Solution<T>>(
+        config: AnnealerConfig,
+        fitness: F,
+        randness: R,
+        rng: Rng,
+    ) -> Result<Self, MincostError> {
+        config.validate()?;
+        let (state, evaluations) =
+            AnnealState::initial_random_state(randness, &fitness, &config, &rng)?;
+        let mut annealer = Annealer {
             config,
             state,
             fitness,
+            cooling: Box::new(GeometricCooling {
+                alpha: config.alpha,
+            }),
+            neighbor: None,
+            level: 0,
+            evaluations,
+            termination: Box::new(MaxIterations(config.iteration)),
+            observers: vec![],
+            run_state: RunState::new(),
+            rng,
+        };
+        let cost = annealer.state.cost;
+        annealer.log_level(&[cost], annealer.state.temperature);
+        Ok(annealer)
+    }
+    /// replace the cooling schedule, [`GeometricCooling`] by `alpha` by default
+    pub fn with_cooling<C: CoolingSchedule + 'static>(mut self, cooling: C) -> Self {
+        self.cooling = Box::new(cooling);
+        self
+    }
+    /// replace the neighbor of a solution, swapping two random bits by default
+    pub fn with_neighbor<N>(mut self, neighbor: N) -> Self
+    where
+        N: Fn(&Solution<T>, &Rng) -> Solution<T> + 'static,
+    {
+        self.neighbor = Some(Box::new(neighbor));
+        self
+    }
+    /// replace the stopping condition, [`MaxIterations`] of `iteration` by default,
+    /// the run also stops once `temperature_end` is reached
+    pub fn with_termination<C: Termination + 'static>(mut self, termination: C) -> Self {
+        self.termination = Box::new(termination);
+        self
+    }
+    /// register an observer invoked after every temperature level
+    pub fn with_observer<B: Observer<T> + 'static>(mut self, observer: B) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+    /// fitness evaluations spent so far
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+    /// progress of the run so far, an annealer has no population diversity
+    pub fn progress(&self) -> Progress {
+        self.run_state.progress(
+            self.evaluations,
+            self.state.best_cost,
+            None,
+            self.config.objective,
+        )
+    }
+    /// criterion which stopped the last run, `None` if it has not stopped yet
+    pub fn stop_reason(&self) -> Option<&StopReason> {
+        self.run_state.stop_reason()
+    }
+    /// statistics of every temperature level so far, over the fitness of neighbors tried
+    /// at the level
+    pub fn history(&self) -> &RunHistory {
+        self.run_state.history()
+    }
+    // record statistics of costs tried at the temperature level
+    fn log_level(&mut self, costs: &[f64], temperature: f32) {
+        let stats = IterationStats {
+            temperature: Some(temperature),
+            ..IterationStats::new(
+                self.run_state.iteration(),
+                self.evaluations,
+                costs,
+                self.state.best_cost,
+                self.config.objective,
+            )
+        };
+        self.run_state.log(stats);
+    }
+    /// solution the annealer currently stays at
+    pub fn current(&self) -> &Solution<T> {
+        &self.state.solution
+    }
+    /// cost of the current solution
+    pub fn current_cost(&self) -> f64 {
+        self.state.cost
+    }
+    /// best solution seen over the whole run
+    pub fn best(&self) -> &Solution<T> {
+        &self.state.best_solution
+    }
+    /// cost of the best solution seen over the whole run
+    pub fn best_cost(&self) -> f64 {
+        self.state.best_cost
+    }
+    // try moves at the current temperature level then cool down, false once
+    // `temperature_end` is reached
+    fn anneal_level(&mut self) -> Result<bool, MincostError> {
+        if self.state.temperature < self.config.temperature_end {
+            return Ok(false);
+        }
+        let best_cost = self.state.best_cost;
+        let mut costs = Vec::with_capacity(self.config.moves_per_temperature);
+        for _ in 0..self.config.moves_per_temperature {
+            let (solution, rng) = (&self.state.solution, &self.rng);
+            let (neighbor, cost, evaluations) = self.config.nan_policy.draw(
+                self.config.objective,
+                || match &self.neighbor {
+                    Some(neighbor) => neighbor(solution, rng),
+                    None => solution.neighbor(rng),
+                },
+                |neighbor| (self.fitness)(neighbor).into(),
+            )?;
+            self.evaluations += evaluations;
+            self.state
+                .acceptance(neighbor, cost, self.config.objective, &self.rng);
+            costs.push(cost);
+        }
+        self.run_state.record(
+            self.config
+                .objective
+                .is_better(&self.state.best_cost, &best_cost),
+        );
+        self.log_level(&costs, self.state.temperature);
+        self.level += 1;
+        self.state.update_temperature(
+            self.cooling.as_mut(),
+            self.config.temperature_zero,
+            self.level,
+        );
+        self.notify();
+        Ok(true)
+    }
+    // show the level to observers, the population is made of the current solution
+    fn notify(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let progress = self.progress();
+        let snapshot = Snapshot {
+            progress,
+            stats: *self.run_state.history().last().unwrap(),
+            best: &self.state.best_solution.bits,
+            population: vec![&self.state.solution.bits],
+        };
+        if observer::notify(&mut self.observers, &snapshot) {
+            self.run_state.request_stop();
         }
     }
+    // anneal until the termination fires or `temperature_end` is reached,
+    // returns the best solution seen
     pub fn anneal(&mut self) -> Solution<T> {
-        for _ in 0..self.config.iteration {
-            if self.state.temperature >= self.config.temperature_end {
-                self.state.acceptance(&self.fitness);
-            } else {
+        self.try_anneal().unwrap_or_else(|e| panic!("{}", e))
+    }
+    // error for an invalid config or genome, before annealing
+    fn check(&self) -> Result<(), MincostError> {
+        self.config.validate()?;
+        if self.state.solution.bits.is_empty() {
+            return Err(MincostError::EmptyGenome);
+        }
+        Ok(())
+    }
+    /// anneal until the termination fires or `temperature_end` is reached, reporting
+    /// an invalid fitness, genome or config as error instead of panicking
+    pub fn try_anneal(&mut self) -> Result<Solution<T>, MincostError> {
+        self.check()?;
+        loop {
+            let progress = self.progress();
+            if self
+                .run_state
+                .should_stop(self.termination.as_ref(), &progress)
+            {
+                break;
+            }
+            if !self.anneal_level()? {
+                self.run_state.stop(StopReason::Exhausted);
                 break;
             }
         }
-        self.state.solution.clone()
+        Ok(self.state.best_solution.clone())
+    }
+}
+
+impl<T> Annealer<T, DynFitness<Solution<T>>>
+where
+    T: Copy + Debug + 'static,
+{
+    /// initial annealer solving `problem`, moving by its neighbor hook
+    pub fn from_problem<P>(config: AnnealerConfig, problem: P) -> Self
+    where
+        P: Problem<T> + 'static,
+        Arc<P>: MaybeSync,
+    {
+        Self::from_problem_with_rng(config, problem, Rng::new())
+    }
+    /// initial annealer solving `problem`, drawing all randomness from `rng`
+    pub fn from_problem_with_rng<P>(config: AnnealerConfig, problem: P, rng: Rng) -> Self
+    where
+        P: Problem<T> + 'static,
+        Arc<P>: MaybeSync,
+    {
+        let problem = Arc::new(problem);
+        let config = AnnealerConfig {
+            objective: problem.objective(),
+            ..config
+        };
+        let randness = |rng: &Rng| Solution {
+            bits: problem.random(rng),
+        };
+        let fitness: DynFitness<Solution<T>> = {
+            let problem = problem.clone();
+            Box::new(move |solution: &Solution<T>| problem.cost(&solution.bits))
+        };
+        let annealer = Self::init_with_rng(config, fitness, randness, rng);
+        annealer.with_neighbor(move |solution: &Solution<T>, rng: &Rng| Solution {
+            bits: problem.neighbor(&solution.bits, rng),
+        })
+    }
+}
+
+impl<T, F, O> Optimizer<T> for Annealer<T, F>
+where
+    F: Fn(&Solution<T>) -> O,
+    O: PartialOrd + Into<f64>,
+    T: Copy + Debug,
+{
+    /// anneal one temperature level
+    fn try_step(&mut self) -> Result<bool, MincostError> {
+        self.check()?;
+        let moved = self.anneal_level()?;
+        Ok(moved && !self.run_state.stop_requested())
+    }
+    fn run(&mut self) -> Vec<T> {
+        self.anneal().bits
+    }
+    fn best(&self) -> &[T] {
+        &self.state.best_solution.bits
+    }
+    fn best_cost(&self) -> f64 {
+        self.state.best_cost
+    }
+    fn stop_reason(&self) -> Option<&StopReason> {
+        self.run_state.stop_reason()
+    }
+    fn history(&self) -> &RunHistory {
+        self.run_state.history()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_same_run, random_tour, tour};
+
+    pub(super) fn annealer(seed: u64) -> Annealer<usize, fn(&Solution<usize>) -> f64> {
+        let config = AnnealerConfig::builder(10.0, 40)
+            .moves_per_temperature(10)
+            .build()
+            .unwrap();
+        Annealer::init_with_rng(
+            config,
+            tour as fn(&_) -> f64,
+            random_tour,
+            Rng::with_seed(seed),
+        )
+    }
+
+    #[test]
+    fn same_seed_gives_identical_run() {
+        assert_same_run(|| annealer(5));
+    }
+
+    #[test]
+    fn temperature_cools_each_level_until_temperature_end() {
+        let config = AnnealerConfig::builder(10.0, 100)
+            .alpha(0.5)
+            .temperature_end(1.0)
+            .build()
+            .unwrap();
+        let mut annealer = Annealer::init_with_rng(config, tour, random_tour, Rng::with_seed(5));
+        annealer.anneal();
+        let levels = &annealer.history().records()[1..];
+        let temperatures: Vec<Option<f32>> = levels.iter().map(|r| r.temperature).collect();
+        assert_eq!(
+            temperatures,
+            vec![Some(10.0), Some(5.0), Some(2.5), Some(1.25)]
+        );
+        assert_eq!(annealer.stop_reason(), Some(&StopReason::Exhausted));
+    }
+
+    #[test]
+    fn out_of_range_config_is_rejected() {
+        let invalid = |builder: AnnealerConfigBuilder| {
+            matches!(builder.build(), Err(MincostError::InvalidConfig(_)))
+        };
+        assert!(invalid(AnnealerConfig::builder(10.0, 3).alpha(1.0)));
+        assert!(invalid(AnnealerConfig::builder(10.0, 3).alpha(0.0)));
+        assert!(invalid(AnnealerConfig::builder(0.0, 3)));
+        assert!(invalid(
+            AnnealerConfig::builder(10.0, 3).temperature_end(0.0)
+        ));
+        assert!(invalid(
+            AnnealerConfig::builder(10.0, 3).temperature_end(-5.0)
+        ));
+        assert!(invalid(
+            AnnealerConfig::builder(10.0, 3).temperature_end(20.0)
+        ));
+        assert!(invalid(
+            AnnealerConfig::builder(10.0, 3).moves_per_temperature(0)
+        ));
+        assert!(AnnealerConfig::builder(10.0, 3)
+            .temperature_end(10.0)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn empty_genome_is_reported_by_try_step() {
+        let config = AnnealerConfig::builder(10.0, 40).build().unwrap();
+        let empty = |_: &Rng| Solution::<usize> { bits: vec![] };
+        let mut annealer = Annealer::init_with_rng(config, tour, empty, Rng::with_seed(5));
+        assert!(matches!(
+            annealer.try_step(),
+            Err(MincostError::EmptyGenome)
+        ));
     }
 }
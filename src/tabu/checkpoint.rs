@@ -0,0 +1,99 @@
+use super::{Candidate, Neighborhood, TabuConfig, TabuSearch};
+use crate::termination::{RunCheckpoint, RunState};
+use crate::MaxIterations;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// saved state of a tabu search between two moves, over moves of type `M`
+///
+/// The checkpoint holds the config, the current and best solutions, the tabu
+/// list, the iteration counter and the history. The search itself draws no
+/// randomness, so there is no `Rng` state to save.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabuCheckpoint<T, M> {
+    config: TabuConfig,
+    current: Candidate<T>,
+    best: Candidate<T>,
+    #[serde(with = "crate::non_finite")]
+    best_cost: f64,
+    tabu_list: Vec<M>,
+    evaluations: usize,
+    run: RunCheckpoint,
+}
+
+impl<T, F, O, N> TabuSearch<T, F, N>
+where
+    F: Fn(&Candidate<T>) -> O,
+    O: PartialOrd + Into<f64>,
+    T: Clone + Debug,
+    N: Neighborhood<T>,
+{
+    /// save the state of the search, to be serialized
+    pub fn checkpoint(&self) -> TabuCheckpoint<T, N::Move> {
+        TabuCheckpoint {
+            config: self.config,
+            current: self.current.clone(),
+            best: self.best.clone(),
+            best_cost: self.best_cost,
+            tabu_list: self.tabu_list.iter().cloned().collect(),
+            evaluations: self.evaluations,
+            run: self.run_state.checkpoint(),
+        }
+    }
+    /// continue the search over `neighborhood` where the checkpoint was saved
+    ///
+    /// Termination and observers start from the defaults of [`TabuSearch::init`].
+    pub fn resume(checkpoint: TabuCheckpoint<T, N::Move>, fitness: F, neighborhood: N) -> Self {
+        let config = checkpoint.config;
+        TabuSearch {
+            config,
+            fitness,
+            neighborhood,
+            current: checkpoint.current,
+            best: checkpoint.best,
+            best_cost: checkpoint.best_cost,
+            tabu_list: checkpoint.tabu_list.into(),
+            evaluations: checkpoint.evaluations,
+            termination: Box::new(MaxIterations(config.iteration)),
+            observers: vec![],
+            run_state: RunState::resume(checkpoint.run),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{tabu_search, Fitness};
+    use super::*;
+    use crate::testing::{assert_resumed_run_matches, failing_tour, random_tour, tour};
+    use crate::{NanPolicy, Objective, Rng, SwapNeighborhood};
+
+    #[test]
+    fn resumed_search_matches_uninterrupted_search() {
+        assert_resumed_run_matches(
+            || tabu_search(1),
+            12,
+            TabuSearch::checkpoint,
+            |checkpoint| TabuSearch::resume(checkpoint, tour as Fitness, SwapNeighborhood),
+        );
+    }
+
+    #[test]
+    fn resumed_search_keeps_worst_fitness() {
+        let config = TabuConfig {
+            tenure: 5,
+            iteration: 30,
+            aspiration: true,
+            objective: Objective::Minimize,
+            nan_policy: NanPolicy::Worst,
+        };
+        let fitness = failing_tour as Fitness;
+        let saved = assert_resumed_run_matches(
+            || TabuSearch::init_with_rng(config, fitness, random_tour, Rng::with_seed(1)),
+            12,
+            TabuSearch::checkpoint,
+            |checkpoint| TabuSearch::resume(checkpoint, fitness, SwapNeighborhood),
+        );
+        assert!(saved.contains("\"NaN\""));
+    }
+}
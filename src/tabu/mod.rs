@@ -0,0 +1,439 @@
+//! Tabu Search Framework
+use crate::error::MincostError;
+use crate::observer;
+use crate::parallel::MaybeSync;
+use crate::termination::RunState;
+use crate::{
+    DynFitness, IterationStats, MaxIterations, NanPolicy, Objective, Observer, Optimizer, Problem,
+    Progress, Rng, RunHistory, Snapshot, StopReason, Termination,
+};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+mod checkpoint;
+
+#[cfg(feature = "serde")]
+pub use checkpoint::*;
+
+/// encoded solution
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Candidate<T> {
+    pub elements: Vec<T>,
+}
+
+/// hyper parameter in tabu search
+#[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TabuConfig {
+    /// number of iterations a move stays tabu
+    pub tenure: usize,
+    /// search iteration number
+    pub iteration: usize,
+    /// accept a tabu move when it leads to a new best solution
+    pub aspiration: bool,
+    /// whether to minimize or maximize the fitness
+    pub objective: Objective,
+    /// what becomes of a neighbor with NaN or infinite fitness
+    pub nan_policy: NanPolicy,
+}
+
+/// user-defined moves around a solution
+pub trait Neighborhood<T> {
+    /// move attribute kept in tabu list
+    type Move: Clone + PartialEq;
+    /// all candidate moves from the solution
+    fn moves(&self, candidate: &Candidate<T>) -> Vec<Self::Move>;
+    /// neighbor solution reached by the move
+    fn apply(&self, candidate: &Candidate<T>, mv: &Self::Move) -> Candidate<T>;
+}
+
+/// Swap neighborhood for permutation encoding, the default neighborhood.
+///
+/// Each move exchanges two elements, identified by their positions.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SwapNeighborhood;
+
+impl<T: Clone> Neighborhood<T> for SwapNeighborhood {
+    type Move = (usize, usize);
+    fn moves(&self, candidate: &Candidate<T>) -> Vec<Self::Move> {
+        let len = candidate.elements.len();
+        (0..len)
+            .flat_map(|i| (i + 1..len).map(move |j| (i, j)))
+            .collect()
+    }
+    fn apply(&self, candidate: &Candidate<T>, mv: &Self::Move) -> Candidate<T> {
+        let mut neighbor = candidate.clone();
+        neighbor.elements.swap(mv.0, mv.1);
+        neighbor
+    }
+}
+
+/// tabu search body
+pub struct TabuSearch<T, F, N: Neighborhood<T>> {
+    config: TabuConfig,
+    fitness: F,
+    neighborhood: N,
+    current: Candidate<T>,
+    best: Candidate<T>,
+    best_cost: f64,
+    tabu_list: VecDeque<N::Move>,
+    evaluations: usize,
+    termination: Box<dyn Termination>,
+    observers: Vec<Box<dyn Observer<T>>>,
+    run_state: RunState,
+}
+
+impl<T, F, O> TabuSearch<T, F, SwapNeighborhood>
+where
+    F: Fn(&Candidate<T>) -> O,
+    O: PartialOrd + Into<f64>,
+    T: Clone + Debug,
+{
+    /// initial tabu search from a random solution, searching by swap moves
+    pub fn init<R: Fn(&Rng) -> Candidate<T>>(config: TabuConfig, fitness: F, randness: R) -> Self {
+        Self::init_with_rng(config, fitness, randness, Rng::new())
+    }
+    /// initial tabu search from a random solution drawn from `rng`, the search itself is deterministic,
+    /// panics on an invalid initial fitness
+    pub fn init_with_rng<R: Fn(&Rng) -> Candidate<T>>(
+        config: TabuConfig,
+        fitness: F,
+        randness: R,
+        rng: Rng,
+    ) -> Self {
+        Self::try_init_with_rng(config, fitness, randness, rng).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// initial tabu search from a random solution, or an error for an invalid initial fitness
+    pub fn try_init<R: Fn(&Rng) -> Candidate<T>>(
+        config: TabuConfig,
+        fitness: F,
+        randness: R,
+    ) -> Result<Self, MincostError> {
+        Self::try_init_with_rng(config, fitness, randness, Rng::new())
+    }
+    /// initial tabu search from a random solution drawn from `rng`, or an error for an
+    /// invalid initial fitness
+    pub fn try_init_with_rng<R: Fn(&Rng) -> Candidate<T>>(
+        config: TabuConfig,
+        fitness: F,
+        randness: R,
+        rng: Rng,
+    ) -> Result<Self, MincostError> {
+        let (current, best_cost, evaluations) = config.nan_policy.draw(
+            config.objective,
+            || randness(&rng),
+            |candidate| fitness(candidate).into(),
+        )?;
+        let mut search = TabuSearch {
+            config,
+            fitness,
+            neighborhood: SwapNeighborhood,
+            best: current.clone(),
+            current,
+            best_cost,
+            tabu_list: VecDeque::with_capacity(config.tenure),
+            evaluations,
+            termination: Box::new(MaxIterations(config.iteration)),
+            observers: vec![],
+            run_state: RunState::new(),
+        };
+        search.log_neighbors(&[best_cost]);
+        Ok(search)
+    }
+}
+
+impl<T> TabuSearch<T, DynFitness<Candidate<T>>, SwapNeighborhood>
+where
+    T: Clone + Debug + 'static,
+{
+    /// initial tabu search solving `problem`, searching by swap moves
+    pub fn from_problem<P>(config: TabuConfig, problem: P) -> Self
+    where
+        P: Problem<T> + 'static,
+        Arc<P>: MaybeSync,
+    {
+        Self::from_problem_with_rng(config, problem, Rng::new())
+    }
+    /// initial tabu search solving `problem` from a random solution drawn from `rng`
+    pub fn from_problem_with_rng<P>(config: TabuConfig, problem: P, rng: Rng) -> Self
+    where
+        P: Problem<T> + 'static,
+        Arc<P>: MaybeSync,
+    {
+        let problem = Arc::new(problem);
+        let config = TabuConfig {
+            objective: problem.objective(),
+            ..config
+        };
+        let randness = |rng: &Rng| Candidate {
+            elements: problem.random(rng),
+        };
+        let fitness: DynFitness<Candidate<T>> = {
+            let problem = problem.clone();
+            Box::new(move |candidate: &Candidate<T>| problem.cost(&candidate.elements))
+        };
+        Self::init_with_rng(config, fitness, randness, rng)
+    }
+}
+
+impl<T, F, O, N> TabuSearch<T, F, N>
+where
+    F: Fn(&Candidate<T>) -> O,
+    O: PartialOrd + Into<f64>,
+    T: Clone + Debug,
+    N: Neighborhood<T>,
+{
+    /// replace the neighborhood, [`SwapNeighborhood`] by default
+    pub fn with_neighborhood<M: Neighborhood<T>>(self, neighborhood: M) -> TabuSearch<T, F, M> {
+        TabuSearch {
+            config: self.config,
+            fitness: self.fitness,
+            neighborhood,
+            current: self.current,
+            best: self.best,
+            best_cost: self.best_cost,
+            tabu_list: VecDeque::with_capacity(self.config.tenure),
+            evaluations: self.evaluations,
+            termination: self.termination,
+            observers: self.observers,
+            run_state: self.run_state,
+        }
+    }
+    /// replace the stopping condition, [`MaxIterations`] of `iteration` by default,
+    /// the search also stops once no admissible move is left
+    pub fn with_termination<C: Termination + 'static>(mut self, termination: C) -> Self {
+        self.termination = Box::new(termination);
+        self
+    }
+    /// register an observer invoked after every move
+    pub fn with_observer<B: Observer<T> + 'static>(mut self, observer: B) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+    /// fitness evaluations spent so far
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+    /// progress of the search so far, a tabu search has no population diversity
+    pub fn progress(&self) -> Progress {
+        self.run_state.progress(
+            self.evaluations,
+            self.best_cost,
+            None,
+            self.config.objective,
+        )
+    }
+    /// criterion which stopped the last search, `None` if it has not stopped yet
+    pub fn stop_reason(&self) -> Option<&StopReason> {
+        self.run_state.stop_reason()
+    }
+    /// statistics of every move so far, over the fitness of neighbors evaluated at the move
+    pub fn history(&self) -> &RunHistory {
+        self.run_state.history()
+    }
+    // record statistics of costs evaluated at the move
+    fn log_neighbors(&mut self, costs: &[f64]) {
+        let stats = IterationStats::new(
+            self.run_state.iteration(),
+            self.evaluations,
+            costs,
+            self.best_cost,
+            self.config.objective,
+        );
+        self.run_state.log(stats);
+    }
+    // show the move to observers, the population is made of the current solution
+    fn notify(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let progress = self.progress();
+        let snapshot = Snapshot {
+            progress,
+            stats: *self.run_state.history().last().unwrap(),
+            best: &self.best.elements,
+            population: vec![&self.current.elements],
+        };
+        if observer::notify(&mut self.observers, &snapshot) {
+            self.run_state.request_stop();
+        }
+    }
+    // move to the best admissible neighbor, false if there is none; a neighbor rejected
+    // by the NaN policy is not admissible
+    fn move_to_neighbor(&mut self) -> Result<bool, MincostError> {
+        let objective = self.config.objective;
+        let mut chosen: Option<(N::Move, Candidate<T>, f64)> = None;
+        let mut costs = vec![];
+        let mut rejected = 0;
+        for mv in self.neighborhood.moves(&self.current) {
+            let neighbor = self.neighborhood.apply(&self.current, &mv);
+            let cost: f64 = (self.fitness)(&neighbor).into();
+            self.evaluations += 1;
+            let cost = match self.config.nan_policy.apply(cost, objective, rejected)? {
+                Some(cost) => cost,
+                None => {
+                    rejected += 1;
+                    continue;
+                }
+            };
+            costs.push(cost);
+            let aspirated = self.config.aspiration && objective.is_better(&cost, &self.best_cost);
+            if self.tabu_list.contains(&mv) && !aspirated {
+                continue;
+            }
+            if chosen
+                .as_ref()
+                .is_none_or(|(_, _, c)| objective.is_better(&cost, c))
+            {
+                chosen = Some((mv, neighbor, cost));
+            }
+        }
+        match chosen {
+            Some((mv, neighbor, cost)) => {
+                let improved = objective.is_better(&cost, &self.best_cost);
+                if improved {
+                    self.best = neighbor.clone();
+                    self.best_cost = cost;
+                }
+                self.run_state.record(improved);
+                self.log_neighbors(&costs);
+                self.notify();
+                self.current = neighbor;
+                self.tabu_list.push_back(mv);
+                while self.tabu_list.len() > self.config.tenure {
+                    self.tabu_list.pop_front();
+                }
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+    /// the top search until the termination fires, returns the best solution found
+    pub fn search(&mut self) -> Candidate<T> {
+        self.try_search().unwrap_or_else(|e| panic!("{}", e))
+    }
+    // error for an empty genome, before searching
+    fn check(&self) -> Result<(), MincostError> {
+        if self.current.elements.is_empty() {
+            return Err(MincostError::EmptyGenome);
+        }
+        Ok(())
+    }
+    /// search until the termination fires, reporting an invalid fitness or genome as
+    /// error instead of panicking
+    pub fn try_search(&mut self) -> Result<Candidate<T>, MincostError> {
+        self.check()?;
+        loop {
+            let progress = self.progress();
+            if self
+                .run_state
+                .should_stop(self.termination.as_ref(), &progress)
+            {
+                break;
+            }
+            if !self.move_to_neighbor()? {
+                self.run_state.stop(StopReason::Exhausted);
+                break;
+            }
+        }
+        Ok(self.best.clone())
+    }
+}
+
+impl<T, F, O, N> Optimizer<T> for TabuSearch<T, F, N>
+where
+    F: Fn(&Candidate<T>) -> O,
+    O: PartialOrd + Into<f64>,
+    T: Clone + Debug,
+    N: Neighborhood<T>,
+{
+    /// move to the best admissible neighbor
+    fn try_step(&mut self) -> Result<bool, MincostError> {
+        self.check()?;
+        let moved = self.move_to_neighbor()?;
+        Ok(moved && !self.run_state.stop_requested())
+    }
+    fn run(&mut self) -> Vec<T> {
+        self.search().elements
+    }
+    fn best(&self) -> &[T] {
+        &self.best.elements
+    }
+    fn best_cost(&self) -> f64 {
+        self.best_cost
+    }
+    fn stop_reason(&self) -> Option<&StopReason> {
+        self.run_state.stop_reason()
+    }
+    fn history(&self) -> &RunHistory {
+        self.run_state.history()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_same_run, random_tour, tour};
+
+    pub(super) type Fitness = fn(&Candidate<usize>) -> f64;
+
+    // misplaced elements, except that swapping the first two elements yields NaN
+    fn misplaced(candidate: &Candidate<usize>) -> f64 {
+        if candidate.elements[..2] == [1, 0] {
+            return f64::NAN;
+        }
+        let elements = candidate.elements.iter().enumerate();
+        elements.filter(|(i, x)| i != *x).count() as f64
+    }
+
+    // search from the sorted permutation, whose first swap move leads to NaN
+    fn search_with(nan_policy: NanPolicy) -> TabuSearch<usize, Fitness, SwapNeighborhood> {
+        let config = TabuConfig {
+            tenure: 2,
+            iteration: 5,
+            aspiration: true,
+            objective: Objective::Minimize,
+            nan_policy,
+        };
+        let randness = |_: &Rng| Candidate {
+            elements: (0..4).collect(),
+        };
+        TabuSearch::init(config, misplaced as Fitness, randness)
+    }
+
+    #[test]
+    fn nan_neighbor_is_reported_as_error() {
+        let result = search_with(NanPolicy::Error).try_search();
+        assert!(matches!(result, Err(MincostError::InvalidFitness(cost)) if cost.is_nan()));
+    }
+
+    #[test]
+    fn nan_neighbor_is_never_moved_to() {
+        for nan_policy in [NanPolicy::Worst, NanPolicy::Resample(1)] {
+            let mut search = search_with(nan_policy);
+            assert!(search.step());
+            assert_eq!(misplaced(&search.current), 2.0);
+            assert_eq!(search.try_search().unwrap().elements, vec![0, 1, 2, 3]);
+            assert_eq!(search.best_cost, 0.0);
+        }
+    }
+
+    pub(super) fn tabu_search(seed: u64) -> TabuSearch<usize, Fitness, SwapNeighborhood> {
+        let config = TabuConfig {
+            tenure: 5,
+            iteration: 30,
+            aspiration: true,
+            objective: Objective::Minimize,
+            nan_policy: NanPolicy::Error,
+        };
+        TabuSearch::init_with_rng(config, tour as Fitness, random_tour, Rng::with_seed(seed))
+    }
+
+    #[test]
+    fn same_seed_gives_identical_search() {
+        assert_same_run(|| tabu_search(11));
+    }
+}
@@ -0,0 +1,316 @@
+//! Termination criteria
+//!
+//! A [`Termination`] looks at the [`Progress`] of a run before every iteration
+//! and tells whether to stop. Criteria are combined by [`AnyOf`] and [`AllOf`],
+//! and the [`StopReason`] of a finished run tells which of them fired.
+//! ```ignore
+//! let mut evolution = Evolution::init(config, fitness, randness).with_termination(
+//!     AnyOf::new()
+//!         .with(MaxEvaluations(10_000))
+//!         .with(TimeLimit(Duration::from_secs(5)))
+//!         .with(Stagnation(50)),
+//! );
+//! ```
+use crate::Objective;
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+use crate::{IterationStats, RunHistory};
+use std::time::Duration;
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+use std::time::Instant;
+
+/// progress of a run, as seen by termination criteria
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Progress {
+    /// iterations done so far, ie. generations, temperature levels or moves
+    pub iteration: usize,
+    /// fitness evaluations spent so far
+    pub evaluations: usize,
+    /// wall-clock time since the optimizer was initialized
+    pub elapsed: Duration,
+    /// fitness of the best solution found so far
+    pub best_cost: f64,
+    /// iterations since the best fitness last improved
+    pub stagnation: usize,
+    /// diversity of the population, `None` for methods keeping a single solution
+    pub diversity: Option<f64>,
+    /// whether the fitness is minimized or maximized
+    pub objective: Objective,
+}
+
+/// criterion firing the end of a run
+#[derive(Debug, PartialEq, Clone)]
+pub enum StopReason {
+    /// [`MaxIterations`] is reached
+    MaxIterations(usize),
+    /// [`MaxEvaluations`] is reached
+    MaxEvaluations(usize),
+    /// [`TimeLimit`] is reached
+    TimeLimit(Duration),
+    /// [`TargetCost`] is reached
+    TargetCost(f64),
+    /// [`Stagnation`] is reached
+    Stagnation(usize),
+    /// [`DiversityBelow`] is reached
+    DiversityBelow(f64),
+    /// every criterion of [`AllOf`] is reached
+    All(Vec<StopReason>),
+    /// the optimizer can not move any further, ie. cooled below `temperature_end`
+    /// or no admissible tabu move is left
+    Exhausted,
+    /// an [`Observer`](crate::Observer) asked to stop
+    Observer,
+}
+
+/// strategy to decide when a run stops
+pub trait Termination {
+    /// reason to stop at the progress, `None` to keep running
+    fn check(&self, progress: &Progress) -> Option<StopReason>;
+}
+
+/// Stop after the number of iterations, the default criterion.
+#[derive(Debug, Copy, Clone)]
+pub struct MaxIterations(pub usize);
+
+/// Stop once the number of fitness evaluations is spent.
+#[derive(Debug, Copy, Clone)]
+pub struct MaxEvaluations(pub usize);
+
+/// Stop once the wall-clock time is spent.
+#[derive(Debug, Copy, Clone)]
+pub struct TimeLimit(pub Duration);
+
+/// Stop once the best fitness is as good as the target.
+#[derive(Debug, Copy, Clone)]
+pub struct TargetCost(pub f64);
+
+/// Stop after the number of iterations without improving the best fitness.
+#[derive(Debug, Copy, Clone)]
+pub struct Stagnation(pub usize);
+
+/// Stop once the population diversity falls below the threshold.
+///
+/// Methods keeping a single solution never report diversity, so the criterion
+/// never fires for them.
+#[derive(Debug, Copy, Clone)]
+pub struct DiversityBelow(pub f64);
+
+/// Stop as soon as any criterion fires.
+#[derive(Default)]
+pub struct AnyOf {
+    criteria: Vec<Box<dyn Termination>>,
+}
+
+/// Stop once every criterion fires at the same time.
+#[derive(Default)]
+pub struct AllOf {
+    criteria: Vec<Box<dyn Termination>>,
+}
+
+impl Termination for MaxIterations {
+    fn check(&self, progress: &Progress) -> Option<StopReason> {
+        (progress.iteration >= self.0).then_some(StopReason::MaxIterations(self.0))
+    }
+}
+
+impl Termination for MaxEvaluations {
+    fn check(&self, progress: &Progress) -> Option<StopReason> {
+        (progress.evaluations >= self.0).then_some(StopReason::MaxEvaluations(self.0))
+    }
+}
+
+impl Termination for TimeLimit {
+    fn check(&self, progress: &Progress) -> Option<StopReason> {
+        (progress.elapsed >= self.0).then_some(StopReason::TimeLimit(self.0))
+    }
+}
+
+impl Termination for TargetCost {
+    fn check(&self, progress: &Progress) -> Option<StopReason> {
+        let reached = !progress.objective.is_better(&self.0, &progress.best_cost);
+        reached.then_some(StopReason::TargetCost(self.0))
+    }
+}
+
+impl Termination for Stagnation {
+    fn check(&self, progress: &Progress) -> Option<StopReason> {
+        (progress.stagnation >= self.0).then_some(StopReason::Stagnation(self.0))
+    }
+}
+
+impl Termination for DiversityBelow {
+    fn check(&self, progress: &Progress) -> Option<StopReason> {
+        let collapsed = progress.diversity.is_some_and(|d| d < self.0);
+        collapsed.then_some(StopReason::DiversityBelow(self.0))
+    }
+}
+
+impl AnyOf {
+    /// combination without any criterion, never fires
+    pub fn new() -> Self {
+        AnyOf { criteria: vec![] }
+    }
+    /// append a criterion
+    pub fn with<C: Termination + 'static>(mut self, criterion: C) -> Self {
+        self.criteria.push(Box::new(criterion));
+        self
+    }
+}
+
+impl AllOf {
+    /// combination without any criterion, never fires
+    pub fn new() -> Self {
+        AllOf { criteria: vec![] }
+    }
+    /// append a criterion
+    pub fn with<C: Termination + 'static>(mut self, criterion: C) -> Self {
+        self.criteria.push(Box::new(criterion));
+        self
+    }
+}
+
+impl Termination for AnyOf {
+    fn check(&self, progress: &Progress) -> Option<StopReason> {
+        self.criteria.iter().find_map(|c| c.check(progress))
+    }
+}
+
+impl Termination for AllOf {
+    fn check(&self, progress: &Progress) -> Option<StopReason> {
+        if self.criteria.is_empty() {
+            return None;
+        }
+        let reasons: Option<Vec<StopReason>> =
+            self.criteria.iter().map(|c| c.check(progress)).collect();
+        reasons.map(StopReason::All)
+    }
+}
+
+// bookkeeping of a run shared by optimizers
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+pub(crate) struct RunState {
+    started: Instant,
+    iteration: usize,
+    stagnation: usize,
+    stop_reason: Option<StopReason>,
+    // an observer asked to stop at the last iteration
+    stop_requested: bool,
+    history: RunHistory,
+}
+
+#[cfg(any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu"))]
+impl RunState {
+    pub(crate) fn new() -> Self {
+        RunState {
+            started: Instant::now(),
+            iteration: 0,
+            stagnation: 0,
+            stop_reason: None,
+            stop_requested: false,
+            history: RunHistory::default(),
+        }
+    }
+    // count one iteration, `improved` if it found a better solution
+    pub(crate) fn record(&mut self, improved: bool) {
+        self.iteration += 1;
+        self.stagnation = if improved { 0 } else { self.stagnation + 1 };
+    }
+    pub(crate) fn iteration(&self) -> usize {
+        self.iteration
+    }
+    pub(crate) fn log(&mut self, stats: IterationStats) {
+        self.history.push(stats);
+    }
+    pub(crate) fn history(&self) -> &RunHistory {
+        &self.history
+    }
+    pub(crate) fn progress(
+        &self,
+        evaluations: usize,
+        best_cost: f64,
+        diversity: Option<f64>,
+        objective: Objective,
+    ) -> Progress {
+        Progress {
+            iteration: self.iteration,
+            evaluations,
+            elapsed: self.started.elapsed(),
+            best_cost,
+            stagnation: self.stagnation,
+            diversity,
+            objective,
+        }
+    }
+    pub(crate) fn stop(&mut self, reason: StopReason) {
+        self.stop_reason = Some(reason);
+    }
+    pub(crate) fn request_stop(&mut self) {
+        self.stop_requested = true;
+    }
+    pub(crate) fn stop_requested(&self) -> bool {
+        self.stop_requested
+    }
+    // whether the run stops before the next iteration, the request of an observer is
+    // consumed so that a later run goes on
+    pub(crate) fn should_stop(
+        &mut self,
+        termination: &dyn Termination,
+        progress: &Progress,
+    ) -> bool {
+        let reason = if std::mem::take(&mut self.stop_requested) {
+            Some(StopReason::Observer)
+        } else {
+            termination.check(progress)
+        };
+        match reason {
+            Some(reason) => {
+                self.stop(reason);
+                true
+            }
+            None => false,
+        }
+    }
+    pub(crate) fn stop_reason(&self) -> Option<&StopReason> {
+        self.stop_reason.as_ref()
+    }
+}
+
+// bookkeeping of a run saved by checkpoints, neither the stop reason nor a pending
+// request of an observer survives, so that the resumed run goes on
+#[cfg(all(
+    feature = "serde",
+    any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu")
+))]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct RunCheckpoint {
+    elapsed: Duration,
+    iteration: usize,
+    stagnation: usize,
+    history: RunHistory,
+}
+
+#[cfg(all(
+    feature = "serde",
+    any(feature = "ga", feature = "sa", feature = "pso", feature = "tabu")
+))]
+impl RunState {
+    pub(crate) fn checkpoint(&self) -> RunCheckpoint {
+        RunCheckpoint {
+            elapsed: self.started.elapsed(),
+            iteration: self.iteration,
+            stagnation: self.stagnation,
+            history: self.history.clone(),
+        }
+    }
+    // the clock goes on from the elapsed time of the checkpoint
+    pub(crate) fn resume(checkpoint: RunCheckpoint) -> Self {
+        let now = Instant::now();
+        RunState {
+            started: now.checked_sub(checkpoint.elapsed).unwrap_or(now),
+            iteration: checkpoint.iteration,
+            stagnation: checkpoint.stagnation,
+            stop_reason: None,
+            stop_requested: false,
+            history: checkpoint.history,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+// fixtures shared by the tests of every optimizer
+use crate::{Optimizer, Rng, RunHistory};
+use std::fmt::Debug;
+
+// solution type of a method, as a tour visiting cities in order
+#[cfg(any(feature = "ga", feature = "sa", feature = "tabu"))]
+pub(crate) trait Tour {
+    fn from_cities(cities: Vec<usize>) -> Self;
+    fn cities(&self) -> &[usize];
+}
+
+#[cfg(feature = "ga")]
+impl Tour for crate::Individual<usize> {
+    fn from_cities(genes: Vec<usize>) -> Self {
+        crate::Individual { genes }
+    }
+    fn cities(&self) -> &[usize] {
+        &self.genes
+    }
+}
+
+#[cfg(feature = "sa")]
+impl Tour for crate::Solution<usize> {
+    fn from_cities(bits: Vec<usize>) -> Self {
+        crate::Solution { bits }
+    }
+    fn cities(&self) -> &[usize] {
+        &self.bits
+    }
+}
+
+#[cfg(feature = "tabu")]
+impl Tour for crate::Candidate<usize> {
+    fn from_cities(elements: Vec<usize>) -> Self {
+        crate::Candidate { elements }
+    }
+    fn cities(&self) -> &[usize] {
+        &self.elements
+    }
+}
+
+// length of a tour over made-up distances, fractional so that a checkpoint has to
+// save costs exactly
+#[cfg(any(feature = "ga", feature = "sa", feature = "tabu"))]
+pub(crate) fn tour<S: Tour>(solution: &S) -> f64 {
+    let legs = solution.cities().windows(2);
+    legs.map(|w| ((w[0] * 7 + w[1] * 3) % 11) as f64)
+        .map(f64::sqrt)
+        .sum()
+}
+
+// tour whose fitness fails unless it starts from city 0, like a simulation failing
+// on most inputs
+#[cfg(all(
+    feature = "serde",
+    any(feature = "ga", feature = "sa", feature = "tabu")
+))]
+pub(crate) fn failing_tour<S: Tour>(solution: &S) -> f64 {
+    if solution.cities()[0] == 0 {
+        tour(solution)
+    } else {
+        f64::NAN
+    }
+}
+
+// random tour over 12 cities
+#[cfg(any(feature = "ga", feature = "sa", feature = "tabu"))]
+pub(crate) fn random_tour<S: Tour>(rng: &Rng) -> S {
+    let mut cities: Vec<usize> = (0..12).collect();
+    rng.shuffle(&mut cities);
+    S::from_cities(cities)
+}
+
+#[cfg(feature = "pso")]
+pub(crate) fn sphere(position: &[f32]) -> f32 {
+    position.iter().map(|x| x * x).sum()
+}
+
+// sphere whose fitness fails on half of the space
+#[cfg(all(feature = "serde", feature = "pso"))]
+pub(crate) fn failing_sphere(position: &[f32]) -> f32 {
+    if position[0] > 0.0 {
+        f32::NAN
+    } else {
+        sphere(position)
+    }
+}
+
+#[cfg(feature = "pso")]
+pub(crate) fn random_particle(rng: &Rng) -> crate::Particle<f32> {
+    crate::Particle::random(&[(-5.0, 5.0); 3], rng)
+}
+
+// best genes and history of a whole run
+fn run<T, O: Optimizer<T>>(optimizer: &mut O) -> (Vec<T>, RunHistory) {
+    let best = optimizer.run();
+    (best, optimizer.history().clone())
+}
+
+// two optimizers built alike, ie. from the same seed, run alike
+pub(crate) fn assert_same_run<T, O>(build: impl Fn() -> O)
+where
+    T: Debug + PartialEq,
+    O: Optimizer<T>,
+{
+    assert_eq!(run(&mut build()), run(&mut build()));
+}
+
+// an optimizer saved to JSON after `steps` steps and resumed runs as if it was never
+// interrupted, returns the saved checkpoint
+#[cfg(feature = "serde")]
+pub(crate) fn assert_resumed_run_matches<T, O, C>(
+    build: impl Fn() -> O,
+    steps: usize,
+    checkpoint: impl Fn(&O) -> C,
+    resume: impl Fn(C) -> O,
+) -> String
+where
+    T: Debug,
+    O: Optimizer<T>,
+    C: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let mut interrupted = build();
+    for _ in 0..steps {
+        interrupted.step();
+    }
+    let saved = serde_json::to_string(&checkpoint(&interrupted)).unwrap();
+    let mut resumed = resume(serde_json::from_str(&saved).unwrap());
+    // compared as debug output, in which NaN statistics are equal
+    let resumed = format!("{:?}", run(&mut resumed));
+    assert_eq!(resumed, format!("{:?}", run(&mut build())));
+    saved
+}